@@ -1,7 +1,8 @@
-use crate::cache::PersistentCache;
+use crate::cache::{self, PersistentCache};
 use crate::views::Views;
 use eframe::egui::{FontData, FontDefinitions, FontFamily};
 use eframe::{CreationContext, egui};
+use team_creator::share::{ShareError, Shared};
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq)]
 pub enum ThemePreference {
@@ -36,6 +37,10 @@ impl Default for SettingsData {
 pub struct App {
     views: Views,
     settings: SettingsData,
+    /// What the share link the app was opened from carries, until it is imported or ignored.
+    shared: Option<Result<Shared, ShareError>>,
+    /// Saved data that couldn't be read at startup, until the user has seen it.
+    warnings: Vec<String>,
 }
 
 impl eframe::App for App {
@@ -69,6 +74,12 @@ impl eframe::App for App {
                     self.views.ui(ui, &mut self.settings);
                 });
         });
+
+        if !self.warnings.is_empty() {
+            self.warnings_modal(ctx);
+        } else if self.shared.is_some() {
+            self.shared_modal(ctx);
+        }
     }
 }
 
@@ -94,6 +105,95 @@ impl App {
         Self {
             views: Views::default(),
             settings: SettingsData::read_or(SettingsData::default()),
+            shared: None,
+            warnings: cache::take_warnings(),
+        }
+    }
+
+    /// Tells which saved data was set aside because it couldn't be read.
+    fn warnings_modal(&mut self, ctx: &egui::Context) {
+        let modal = egui::Modal::new(egui::Id::new("Warnings Modal")).show(ctx, |ui| {
+            ui.set_width(400.0);
+            ui.heading("⚠ Saved data could not be read");
+            ui.separator();
+            for warning in &self.warnings {
+                ui.colored_label(ui.visuals().warn_fg_color, warning);
+            }
+            ui.separator();
+            egui::Sides::new().show(
+                ui,
+                |_ui| {},
+                |ui| {
+                    if ui.button("✔ OK").clicked() {
+                        ui.close();
+                    }
+                },
+            );
+        });
+
+        if modal.should_close() {
+            self.warnings.clear();
+        }
+    }
+
+    /// Offers to import what a share link carries once the app is up.
+    #[cfg(target_arch = "wasm32")]
+    pub fn with_shared(mut self, shared: Option<Result<Shared, ShareError>>) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    /// Asks whether to import what the share link carries. Nothing is replaced: a roster is added
+    /// next to the existing ones and wheel entries are added to the wheel.
+    fn shared_modal(&mut self, ctx: &egui::Context) {
+        let mut import = false;
+
+        let modal = egui::Modal::new(egui::Id::new("Shared Modal")).show(ctx, |ui| {
+            ui.set_width(300.0);
+            ui.heading("Shared link");
+            ui.separator();
+            match &self.shared {
+                Some(Ok(shared)) => {
+                    ui.label(format!("This link shares a {}.", shared.describe()));
+                    ui.label(match shared {
+                        Shared::Roster { .. } => {
+                            "It will be added as a new roster, your rosters are kept."
+                        }
+                        Shared::Wheel { .. } => {
+                            "Its entries will be added to your wheel, the ones you have are kept."
+                        }
+                    });
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("The link could not be read: {}", error),
+                    );
+                }
+                None => {}
+            }
+            ui.separator();
+            egui::Sides::new().show(
+                ui,
+                |_ui| {},
+                |ui| {
+                    if matches!(self.shared, Some(Ok(_))) && ui.button("📥 Import").clicked() {
+                        import = true;
+                        ui.close();
+                    }
+
+                    if ui.button("❌ Ignore").clicked() {
+                        ui.close();
+                    }
+                },
+            );
+        });
+
+        if modal.should_close() {
+            if import && let Some(Ok(shared)) = &self.shared {
+                self.views.import(shared);
+            }
+            self.shared = None;
         }
     }
 
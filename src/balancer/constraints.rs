@@ -0,0 +1,352 @@
+use super::Player;
+use std::collections::HashMap;
+use std::fmt;
+use ulid::Ulid;
+
+/// How a [`Constraint`] treats its players.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ConstraintKind {
+    /// Every listed player ends up on the same team.
+    Together,
+    /// No two listed players end up on the same team.
+    Apart,
+}
+
+impl ConstraintKind {
+    /// Short label with an icon, for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConstraintKind::Together => "🔗 Together",
+            ConstraintKind::Apart => "⛔ Apart",
+        }
+    }
+}
+
+/// A rule between two or more players, referenced by [`Player::id`]. Ids that are not part of
+/// the roster being split are ignored.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Constraint {
+    pub kind: ConstraintKind,
+    pub players: Vec<Ulid>,
+}
+
+impl Constraint {
+    /// A constraint with no players yet.
+    pub fn new(kind: ConstraintKind) -> Self {
+        Constraint {
+            kind,
+            players: Vec::new(),
+        }
+    }
+}
+
+/// Why the roster could not be split.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplitError {
+    /// A group that has to stay together does not fit in the largest team.
+    GroupTooLarge {
+        names: Vec<String>,
+        team_size: usize,
+    },
+    /// Two players are required to be both together and apart.
+    Contradiction { first: String, second: String },
+    /// More players must be kept apart than there are teams.
+    TooManyApart {
+        names: Vec<String>,
+        team_count: usize,
+    },
+    /// The requested team sizes do not add up to the number of players.
+    TeamSizesMismatch { slots: usize, players: usize },
+    /// Teams need more role slots filled than they have players.
+    TooManyRoles { slots: usize, team_size: usize },
+    /// Not enough players can play a role to fill it on every team.
+    RoleShortage {
+        role: String,
+        needed: usize,
+        available: usize,
+    },
+    /// Two captains are required to be on the same team.
+    CaptainsTogether { first: String, second: String },
+    /// No split satisfying every constraint was found.
+    Unsatisfiable,
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::GroupTooLarge { names, team_size } => write!(
+                f,
+                "{} must play together, but teams only have {} slots",
+                names.join(", "),
+                team_size
+            ),
+            SplitError::Contradiction { first, second } => write!(
+                f,
+                "{} and {} are required to be both together and apart",
+                first, second
+            ),
+            SplitError::TooManyApart { names, team_count } => write!(
+                f,
+                "{} must all be kept apart, but there are only {} teams",
+                names.join(", "),
+                team_count
+            ),
+            SplitError::TeamSizesMismatch { slots, players } => write!(
+                f,
+                "Team sizes add up to {} slots, but there are {} players",
+                slots, players
+            ),
+            SplitError::TooManyRoles { slots, team_size } => write!(
+                f,
+                "Every team needs {} role slots filled, but the smallest team only has {} players",
+                slots, team_size
+            ),
+            SplitError::RoleShortage {
+                role,
+                needed,
+                available,
+            } => write!(
+                f,
+                "{} players are needed as {}, but only {} can play it",
+                needed, role, available
+            ),
+            SplitError::CaptainsTogether { first, second } => write!(
+                f,
+                "Captains {} and {} cannot be on the same team",
+                first, second
+            ),
+            SplitError::Unsatisfiable => {
+                write!(f, "No split satisfies every constraint")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+/// Players that have to be placed on the same team.
+pub(super) struct Unit {
+    pub players: Vec<Player>,
+    /// Team this unit is pinned to because it contains that team's captain.
+    pub team: Option<usize>,
+}
+
+/// Groups `players` into [`Unit`]s and returns them together with a matrix of which units must
+/// not share a team. When `has_captains` is set, the i-th captain is pinned to team i.
+pub(super) fn build_units(
+    players: &[Player],
+    constraints: &[Constraint],
+    team_count: usize,
+    team_size: usize,
+    has_captains: bool,
+) -> Result<(Vec<Unit>, Vec<Vec<bool>>), SplitError> {
+    let index_of: HashMap<Ulid, usize> = players
+        .iter()
+        .enumerate()
+        .map(|(idx, p)| (p.id, idx))
+        .collect();
+    let members = |constraint: &Constraint| -> Vec<usize> {
+        let mut members: Vec<usize> = constraint
+            .players
+            .iter()
+            .filter_map(|id| index_of.get(id).copied())
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        members
+    };
+
+    // Union-find over player indices for the "together" constraints
+    let mut parent: Vec<usize> = (0..players.len()).collect();
+    fn find(parent: &mut [usize], idx: usize) -> usize {
+        let mut root = idx;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[idx] = root;
+        root
+    }
+
+    for constraint in constraints
+        .iter()
+        .filter(|c| c.kind == ConstraintKind::Together)
+    {
+        let members = members(constraint);
+        for pair in members.windows(2) {
+            let (a, b) = (find(&mut parent, pair[0]), find(&mut parent, pair[1]));
+            parent[a] = b;
+        }
+    }
+
+    let mut unit_of_root: HashMap<usize, usize> = HashMap::new();
+    let mut unit_of_player = vec![0; players.len()];
+    let mut units: Vec<Unit> = Vec::new();
+    let mut captain_idx = 0;
+
+    for (idx, player) in players.iter().enumerate() {
+        let root = find(&mut parent, idx);
+        let unit_idx = *unit_of_root.entry(root).or_insert_with(|| {
+            units.push(Unit {
+                players: Vec::new(),
+                team: None,
+            });
+            units.len() - 1
+        });
+        unit_of_player[idx] = unit_idx;
+
+        let unit = &mut units[unit_idx];
+        if has_captains && player.is_captain {
+            if let Some(other) = unit.players.iter().find(|p| p.is_captain) {
+                return Err(SplitError::CaptainsTogether {
+                    first: other.name.clone(),
+                    second: player.name.clone(),
+                });
+            }
+            unit.team = Some(captain_idx);
+            captain_idx += 1;
+        }
+        unit.players.push(player.clone());
+    }
+
+    if let Some(unit) = units.iter().find(|u| u.players.len() > team_size) {
+        return Err(SplitError::GroupTooLarge {
+            names: unit.players.iter().map(|p| p.name.clone()).collect(),
+            team_size,
+        });
+    }
+
+    let mut conflicts = vec![vec![false; units.len()]; units.len()];
+    for constraint in constraints
+        .iter()
+        .filter(|c| c.kind == ConstraintKind::Apart)
+    {
+        let members = members(constraint);
+        if members.len() > team_count {
+            return Err(SplitError::TooManyApart {
+                names: members.iter().map(|&i| players[i].name.clone()).collect(),
+                team_count,
+            });
+        }
+
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                let (unit_a, unit_b) = (unit_of_player[a], unit_of_player[b]);
+                if unit_a == unit_b {
+                    return Err(SplitError::Contradiction {
+                        first: players[a].name.clone(),
+                        second: players[b].name.clone(),
+                    });
+                }
+                conflicts[unit_a][unit_b] = true;
+                conflicts[unit_b][unit_a] = true;
+            }
+        }
+    }
+
+    Ok((units, conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(count: usize) -> Vec<Player> {
+        (0..count)
+            .map(|idx| Player {
+                name: format!("P{}", idx),
+                skill: 100,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn constraint(kind: ConstraintKind, players: &[&Player]) -> Constraint {
+        Constraint {
+            kind,
+            players: players.iter().map(|p| p.id).collect(),
+        }
+    }
+
+    #[test]
+    fn together_players_share_a_unit() {
+        let players = players(5);
+        let constraints = [
+            constraint(ConstraintKind::Together, &[&players[0], &players[1]]),
+            constraint(ConstraintKind::Together, &[&players[1], &players[2]]),
+        ];
+        let (units, _) = build_units(&players, &constraints, 2, 3, false).unwrap();
+        let sizes: Vec<usize> = units.iter().map(|u| u.players.len()).collect();
+        assert_eq!(sizes, [3, 1, 1]);
+    }
+
+    #[test]
+    fn apart_players_conflict() {
+        let players = players(4);
+        let constraints = [constraint(
+            ConstraintKind::Apart,
+            &[&players[0], &players[3]],
+        )];
+        let (_, conflicts) = build_units(&players, &constraints, 2, 2, false).unwrap();
+        assert!(conflicts[0][3] && conflicts[3][0]);
+        assert!(!conflicts[0][1]);
+    }
+
+    #[test]
+    fn unknown_players_are_ignored() {
+        let players = players(4);
+        let outsider = Player::default();
+        let constraints = [constraint(
+            ConstraintKind::Together,
+            &[&players[0], &outsider],
+        )];
+        let (units, _) = build_units(&players, &constraints, 2, 2, false).unwrap();
+        assert_eq!(units.len(), 4);
+    }
+
+    #[test]
+    fn impossible_constraints_are_rejected() {
+        let players = players(6);
+        let together = constraint(
+            ConstraintKind::Together,
+            &[&players[0], &players[1], &players[2], &players[3]],
+        );
+        assert!(matches!(
+            build_units(&players, &[together], 2, 3, false),
+            Err(SplitError::GroupTooLarge { team_size: 3, .. })
+        ));
+
+        let constraints = [
+            constraint(ConstraintKind::Together, &[&players[0], &players[1]]),
+            constraint(ConstraintKind::Apart, &[&players[1], &players[0]]),
+        ];
+        assert!(matches!(
+            build_units(&players, &constraints, 2, 3, false),
+            Err(SplitError::Contradiction { .. })
+        ));
+
+        let apart = constraint(
+            ConstraintKind::Apart,
+            &[&players[0], &players[1], &players[2]],
+        );
+        assert!(matches!(
+            build_units(&players, &[apart], 2, 3, false),
+            Err(SplitError::TooManyApart { team_count: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn captains_are_pinned_and_kept_apart() {
+        let mut players = players(4);
+        players[1].is_captain = true;
+        players[3].is_captain = true;
+        let (units, _) = build_units(&players, &[], 2, 2, true).unwrap();
+        let pinned: Vec<Option<usize>> = units.iter().map(|u| u.team).collect();
+        assert_eq!(pinned, [None, Some(0), None, Some(1)]);
+
+        let together = constraint(ConstraintKind::Together, &[&players[1], &players[3]]);
+        assert!(matches!(
+            build_units(&players, &[together], 2, 2, true),
+            Err(SplitError::CaptainsTogether { .. })
+        ));
+    }
+}
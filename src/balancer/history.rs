@@ -0,0 +1,93 @@
+use super::Player;
+use std::collections::HashMap;
+use ulid::Ulid;
+
+/// How many of the most recent games count towards the teammate penalty.
+pub const HISTORY_WINDOW: u32 = 5;
+
+/// Teams of a previously generated game.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PastGame {
+    pub game: u32,
+    pub teams: Vec<Vec<PastPlayer>>,
+}
+
+/// A player as they were when the game was recorded, so the history survives roster changes.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PastPlayer {
+    pub id: Ulid,
+    pub name: String,
+    #[serde(default)]
+    pub skill: u32,
+    #[serde(default)]
+    pub is_captain: bool,
+}
+
+impl PastPlayer {
+    pub fn new(player: &Player) -> Self {
+        PastPlayer {
+            id: player.id,
+            name: player.name.clone(),
+            skill: player.skill,
+            is_captain: player.is_captain,
+        }
+    }
+}
+
+impl PastGame {
+    pub fn new(game: u32, teams: &[Vec<Player>]) -> Self {
+        PastGame {
+            game,
+            teams: teams
+                .iter()
+                .map(|team| team.iter().map(PastPlayer::new).collect())
+                .collect(),
+        }
+    }
+}
+
+/// How recently each pair of players were teammates, keyed by `(smaller id, larger id)`. Being
+/// teammates in the latest game counts [`HISTORY_WINDOW`], one game earlier counts one less, and
+/// so on, summed over the window.
+pub fn teammate_repeats(history: &[PastGame]) -> HashMap<(Ulid, Ulid), u32> {
+    let mut repeats = HashMap::new();
+    let Some(latest) = history.iter().map(|g| g.game).max() else {
+        return repeats;
+    };
+
+    for past in history {
+        let age = latest - past.game;
+        if age >= HISTORY_WINDOW {
+            continue;
+        }
+
+        for team in &past.teams {
+            for (i, a) in team.iter().enumerate() {
+                for b in &team[i + 1..] {
+                    *repeats.entry(pair_key(a.id, b.id)).or_insert(0) += HISTORY_WINDOW - age;
+                }
+            }
+        }
+    }
+
+    repeats
+}
+
+/// Number of pairs in `teams` that were teammates within the last [`HISTORY_WINDOW`] games.
+pub fn repeated_pairs(teams: &[Vec<Player>], history: &[PastGame]) -> usize {
+    let repeats = teammate_repeats(history);
+    teams
+        .iter()
+        .map(|team| {
+            team.iter()
+                .enumerate()
+                .flat_map(|(i, a)| team[i + 1..].iter().map(move |b| (a.id, b.id)))
+                .filter(|&(a, b)| repeats.contains_key(&pair_key(a, b)))
+                .count()
+        })
+        .sum()
+}
+
+pub(super) fn pair_key(a: Ulid, b: Ulid) -> (Ulid, Ulid) {
+    if a < b { (a, b) } else { (b, a) }
+}
@@ -0,0 +1,160 @@
+use super::Player;
+use std::fmt;
+
+/// How team totals are compared to tell how unbalanced a split is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Metric {
+    /// The gap between two teams, or the sum of squared deviations from the average team.
+    #[default]
+    Variance,
+    /// Difference between the strongest and the weakest team.
+    Spread,
+    /// Sum of every team's distance from the average team.
+    AbsoluteDeviation,
+    /// [`Metric::Spread`], plus the difference between the teams' best players.
+    StrongestPlayer,
+    /// [`Metric::Spread`], plus the rating of every top player who shares a team with a better
+    /// top player. There are as many top players as teams.
+    TopHeavy,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 5] = [
+        Metric::Variance,
+        Metric::Spread,
+        Metric::AbsoluteDeviation,
+        Metric::StrongestPlayer,
+        Metric::TopHeavy,
+    ];
+
+    /// Short name, for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::Variance => "Variance",
+            Metric::Spread => "Spread",
+            Metric::AbsoluteDeviation => "Absolute deviation",
+            Metric::StrongestPlayer => "Strongest player",
+            Metric::TopHeavy => "Top-heavy",
+        }
+    }
+
+    /// One sentence on what is minimized, for display.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Metric::Variance => {
+                "Gap between two teams, or how far teams are from the average, \
+                 with big differences weighing much more"
+            }
+            Metric::Spread => "Gap between the strongest and the weakest team",
+            Metric::AbsoluteDeviation => "How far all teams are from the average team combined",
+            Metric::StrongestPlayer => {
+                "Gap between the strongest and the weakest team, \
+                 plus the gap between the teams' best players"
+            }
+            Metric::TopHeavy => {
+                "Gap between the strongest and the weakest team, \
+                 plus a penalty for each team stacking more than one top player"
+            }
+        }
+    }
+
+    /// Imbalance of one dimension of team totals.
+    pub(super) fn score(&self, sums: &[i64]) -> i64 {
+        match self {
+            Metric::Variance => variance_of_sums(sums),
+            Metric::Spread | Metric::StrongestPlayer | Metric::TopHeavy => spread(sums),
+            Metric::AbsoluteDeviation => absolute_deviation(sums),
+        }
+    }
+
+    /// Smallest [`Metric::score`] any completion of the partial sums can reach, given their final
+    /// `total`. Sums only ever grow while the remaining players are placed.
+    pub(super) fn lower_bound(&self, sums: &[i64], total: i64) -> i64 {
+        if sums.len() < 2 {
+            return 0;
+        }
+
+        match self {
+            Metric::Variance => {
+                if sums.len() == 2 {
+                    sums.iter()
+                        .map(|&s| (2 * s - total).max(0))
+                        .max()
+                        .unwrap_or(0)
+                } else {
+                    let avg = total / sums.len() as i64;
+                    sums.iter().map(|&s| (s - avg).max(0).pow(2)).sum()
+                }
+            }
+            Metric::Spread | Metric::StrongestPlayer | Metric::TopHeavy => {
+                // The weakest team gets at most an even share of what the strongest one leaves
+                let max = sums.iter().copied().max().unwrap_or(0);
+                let weakest = (total - max).div_euclid(sums.len() as i64 - 1);
+                (max - weakest).max(0)
+            }
+            Metric::AbsoluteDeviation => {
+                let avg = total / sums.len() as i64;
+                sums.iter().map(|&s| (s - avg).max(0)).sum()
+            }
+        }
+    }
+
+    /// Penalty on how the best players are spread, on top of the team totals. Only counts what
+    /// placing more players can't lower when `complete` is false.
+    pub(super) fn player_penalty(
+        &self,
+        teams: &[Vec<Player>],
+        ratings: impl Fn(&Player) -> i64,
+        top_threshold: i64,
+        complete: bool,
+    ) -> i64 {
+        match self {
+            Metric::StrongestPlayer if complete => {
+                let best: Vec<i64> = teams
+                    .iter()
+                    .map(|team| team.iter().map(&ratings).max().unwrap_or(0))
+                    .collect();
+                spread(&best)
+            }
+            Metric::TopHeavy => teams
+                .iter()
+                .map(|team| {
+                    let top: Vec<i64> = team
+                        .iter()
+                        .map(&ratings)
+                        .filter(|&rating| rating >= top_threshold)
+                        .collect();
+                    top.iter().sum::<i64>() - top.iter().copied().max().unwrap_or(0)
+                })
+                .sum(),
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// The gap for two teams, otherwise the sum of squared deviations from the average team.
+fn variance_of_sums(sums: &[i64]) -> i64 {
+    if sums.len() == 2 {
+        (sums[0] - sums[1]).abs()
+    } else {
+        let avg = sums.iter().sum::<i64>() / sums.len() as i64;
+        sums.iter().map(|&s| (s - avg).pow(2)).sum()
+    }
+}
+
+fn spread(sums: &[i64]) -> i64 {
+    let max = sums.iter().copied().max().unwrap_or(0);
+    let min = sums.iter().copied().min().unwrap_or(0);
+    max - min
+}
+
+fn absolute_deviation(sums: &[i64]) -> i64 {
+    let avg = sums.iter().sum::<i64>() / sums.len().max(1) as i64;
+    sums.iter().map(|&s| (s - avg).abs()).sum()
+}
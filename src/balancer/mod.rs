@@ -0,0 +1,923 @@
+//! Splitting a roster into balanced teams.
+
+mod constraints;
+mod history;
+mod metric;
+mod objective;
+mod roles;
+
+pub use constraints::{Constraint, ConstraintKind, SplitError};
+pub use history::{HISTORY_WINDOW, PastGame, PastPlayer, repeated_pairs, teammate_repeats};
+pub use metric::Metric;
+pub use roles::{Role, assign_roles, unfilled_slots};
+
+use constraints::{Unit, build_units};
+use objective::Objective;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use ulid::Ulid;
+
+/// Rosters up to this size are split by the exhaustive solver, larger ones fall back to the
+/// randomized greedy heuristic.
+const EXACT_SOLVER_MAX_PLAYERS: usize = 14;
+/// Random greedy splits tried for rosters too large for the exhaustive solver.
+const GREEDY_ITERATIONS: usize = 100;
+
+/// Highest skill, or value of an attribute, a player can have. Keeps the totals of teams far
+/// from overflowing.
+pub const MAX_SKILL: u32 = 35000;
+
+/// Someone on the roster.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Player {
+    #[serde(default = "Ulid::new")]
+    pub id: Ulid,
+    pub name: String,
+    pub skill: u32,
+    #[serde(default)]
+    pub is_captain: bool,
+    /// Game number this player last sat out, see [`pick_bench`].
+    #[serde(default)]
+    pub last_benched: Option<u32>,
+    /// Values of the roster's [`Attribute`]s, missing ones count as zero.
+    #[serde(default)]
+    pub attributes: BTreeMap<Ulid, u32>,
+    /// Ids of the [`Role`]s this player can fill.
+    #[serde(default)]
+    pub roles: BTreeSet<Ulid>,
+    /// Free-form labels, like "goalkeeper" or "new".
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+/// A named numeric trait players are rated on besides their skill, like "AWP" or "Entry".
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Attribute {
+    pub id: Ulid,
+    pub name: String,
+    /// How many skill points one point of this attribute is worth, in percent. Also scales how
+    /// much an uneven spread of the attribute across teams is penalized.
+    pub weight: u32,
+}
+
+impl Attribute {
+    /// A new attribute worth as much as skill.
+    pub fn new(name: String) -> Self {
+        Attribute {
+            id: Ulid::new(),
+            name,
+            weight: 100,
+        }
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Player {
+            id: Ulid::new(),
+            name: String::new(),
+            skill: 0,
+            is_captain: false,
+            last_benched: None,
+            attributes: BTreeMap::new(),
+            roles: BTreeSet::new(),
+            tags: Vec::new(),
+            notes: String::new(),
+        }
+    }
+}
+
+impl Player {
+    /// Name with a star for captains and, unless hidden, the skill in parentheses.
+    pub fn pretty_name(&self, hide_skill: bool) -> String {
+        format!(
+            "{}{}{}",
+            if self.is_captain { "⭐ " } else { "" },
+            if self.name.is_empty() {
+                "<unnamed>"
+            } else {
+                &self.name
+            },
+            if hide_skill {
+                String::new()
+            } else {
+                format!(" ({})", self.skill)
+            },
+        )
+    }
+
+    /// Value of the attribute with the given id, zero if the player has none.
+    pub fn attribute(&self, id: Ulid) -> u32 {
+        self.attributes.get(&id).copied().unwrap_or(0)
+    }
+
+    /// Skill plus every attribute scaled by its weight.
+    pub fn rating(&self, attributes: &[Attribute]) -> u32 {
+        self.skill
+            + attributes
+                .iter()
+                .map(|a| self.attribute(a.id) * a.weight / 100)
+                .sum::<u32>()
+    }
+}
+
+/// Total skill of a team.
+pub fn sum_skill(team: &[Player]) -> u32 {
+    team.iter().map(|p| p.skill).sum::<u32>()
+}
+
+/// Total of one attribute over a team.
+pub fn sum_attribute(team: &[Player], id: Ulid) -> u32 {
+    team.iter().map(|p| p.attribute(id)).sum::<u32>()
+}
+
+/// Order of players by who they are rather than by id, as players read from files without ids
+/// get new ones every time. Shuffling from it keeps seeds reproducible. Ids only break ties
+/// between players alike in everything else.
+fn canonical_order(a: &Player, b: &Player) -> Ordering {
+    (&a.name, a.skill, a.is_captain, &a.attributes, &a.roles)
+        .cmp(&(&b.name, b.skill, b.is_captain, &b.attributes, &b.roles))
+        .then(a.id.cmp(&b.id))
+}
+
+/// Picks `count` players to sit out and returns `(playing, bench)`. Whoever sat out longest ago,
+/// or never, goes to the bench first, ties are broken randomly. Captains always play.
+pub fn pick_bench(
+    players: &[Player],
+    count: usize,
+    rng: &mut impl Rng,
+) -> (Vec<Player>, Vec<Player>) {
+    let mut candidates: Vec<Player> = players.iter().filter(|p| !p.is_captain).cloned().collect();
+    // Shuffle from a fixed order, so the same roster and rng always bench the same players
+    candidates.sort_by(canonical_order);
+    candidates.shuffle(rng);
+    candidates.sort_by_key(|p| p.last_benched);
+    candidates.truncate(count);
+
+    let playing = players
+        .iter()
+        .filter(|p| !candidates.iter().any(|benched| benched.id == p.id))
+        .cloned()
+        .collect();
+
+    (playing, candidates)
+}
+
+/// Settings for [`best_balanced_split`] besides the roster itself.
+pub struct SplitOptions<'a> {
+    pub team_count: usize,
+    /// Number of players on each team. When empty, the roster is split as evenly as possible.
+    pub team_sizes: &'a [usize],
+    /// Extra skill a team has to receive for every slot it is short, in percent of the average
+    /// player's skill.
+    pub short_handed_bonus: u32,
+    /// Attributes balanced on top of skill.
+    pub attributes: &'a [Attribute],
+    /// Roles every team has to fill before skill is considered.
+    pub roles: &'a [Role],
+    pub constraints: &'a [Constraint],
+    /// Previous games, used to avoid putting recent teammates together again.
+    pub history: &'a [PastGame],
+    /// How much imbalance one point of [`teammate_repeats`] is worth. Zero ignores the history and
+    /// only balances skill, higher values trade balance for fresh teams.
+    pub fresh_teams_weight: u32,
+    /// How the imbalance of a split is measured.
+    pub metric: Metric,
+    /// How many distinct splits to return at most.
+    pub candidates: usize,
+}
+
+/// One way of splitting the roster into teams, together with its imbalance score.
+#[derive(Clone, Debug)]
+pub struct Split {
+    pub teams: Vec<Vec<Player>>,
+    /// Imbalance as measured by [`SplitOptions::metric`], lower is better.
+    pub score: i64,
+    /// Extra skill each team was given on top of an even share to make up for missing players.
+    pub handicaps: Vec<u32>,
+}
+
+/// Returns up to `options.candidates` distinct splits ordered from most to least balanced. Two
+/// splits are the same if every team has the same members, regardless of team or player order.
+///
+/// Only splits where every team can fill its [`Role`] slots are considered. Among those, teams
+/// are balanced on their combined [`Player::rating`] and, secondarily, on the total of each
+/// attribute. Teams with fewer players than the largest team are balanced as if they were
+/// [`Split::handicaps`] weaker, so they end up with that much more rating than the others.
+/// Putting recent teammates together again costs [`SplitOptions::fresh_teams_weight`] per point.
+///
+/// All randomness comes from `rng`, so the same roster and a generator seeded the same way always
+/// produce the same splits, whatever order the players are given in.
+pub fn best_balanced_split(
+    players: &mut [Player],
+    options: &SplitOptions,
+    rng: &mut impl Rng,
+) -> Result<Vec<Split>, SplitError> {
+    players.sort_by(canonical_order);
+    players.shuffle(rng);
+
+    let team_count = options.team_count;
+    let sizes = team_sizes(players.len(), options)?;
+    let handicaps = handicaps(players, &sizes, options);
+    let objective = Objective::new(
+        players,
+        options.attributes,
+        handicaps,
+        teammate_penalties(options),
+        options.metric,
+    );
+    let mut best = SplitCandidates::new(options.candidates, &objective);
+
+    if team_count == 1 {
+        best.offer(vec![players.to_vec()]);
+        return Ok(best.splits);
+    }
+
+    check_roles(players, &sizes, options.roles)?;
+
+    // Captains lead one team each, but only if there is exactly one per team
+    let has_captains = players.iter().filter(|p| p.is_captain).count() == team_count;
+    let largest_team = sizes.iter().copied().max().unwrap_or(0);
+    let (units, conflicts) = build_units(
+        players,
+        options.constraints,
+        team_count,
+        largest_team,
+        has_captains,
+    )?;
+    let unit_values: Vec<Vec<i64>> = units
+        .iter()
+        .map(|unit| objective.group_values(&unit.players))
+        .collect();
+
+    if players.len() <= EXACT_SOLVER_MAX_PLAYERS {
+        exact_split(
+            &units,
+            &unit_values,
+            &conflicts,
+            &sizes,
+            options.roles,
+            has_captains,
+            &mut best,
+        );
+    } else {
+        // Larger rosters are split with an iterative greedy approach
+        // which is more efficient than enumerating all possible combinations
+        for _ in 0..GREEDY_ITERATIONS {
+            if let Some(teams) = create_greedy_teams(
+                &units,
+                &unit_values,
+                &conflicts,
+                &sizes,
+                options.roles,
+                &objective,
+                rng,
+            ) {
+                best.offer(teams);
+            }
+        }
+    }
+
+    if best.splits.is_empty() {
+        Err(SplitError::Unsatisfiable)
+    } else {
+        Ok(best.splits)
+    }
+}
+
+/// Resolves how many players each team gets. Without explicit sizes the first
+/// `player_count % team_count` teams get one extra player.
+fn team_sizes(player_count: usize, options: &SplitOptions) -> Result<Vec<usize>, SplitError> {
+    let team_count = options.team_count;
+
+    if options.team_sizes.is_empty() {
+        return Ok((0..team_count)
+            .map(|idx| player_count / team_count + usize::from(idx < player_count % team_count))
+            .collect());
+    }
+
+    let slots: usize = options.team_sizes.iter().sum();
+    if options.team_sizes.len() != team_count || slots != player_count {
+        return Err(SplitError::TeamSizesMismatch {
+            slots,
+            players: player_count,
+        });
+    }
+
+    Ok(options.team_sizes.to_vec())
+}
+
+/// Rejects role requirements that no split can meet: more slots per team than players, or too
+/// few players able to play a role.
+fn check_roles(players: &[Player], sizes: &[usize], roles: &[Role]) -> Result<(), SplitError> {
+    let slots: usize = roles.iter().map(|r| r.per_team).sum();
+    let smallest_team = sizes.iter().copied().min().unwrap_or(0);
+    if slots > smallest_team {
+        return Err(SplitError::TooManyRoles {
+            slots,
+            team_size: smallest_team,
+        });
+    }
+
+    for role in roles {
+        let needed = role.per_team * sizes.len();
+        let available = players
+            .iter()
+            .filter(|p| p.roles.contains(&role.id))
+            .count();
+        if available < needed {
+            return Err(SplitError::RoleShortage {
+                role: role.name.clone(),
+                needed,
+                available,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `team` can still fill all its role slots with the room it has left.
+fn fits_roles(team: &[Player], size: usize, roles: &[Role]) -> bool {
+    roles.is_empty() || unfilled_slots(team, roles) <= size.saturating_sub(team.len())
+}
+
+/// Cost of putting each pair of recent teammates on the same team again.
+fn teammate_penalties(options: &SplitOptions) -> HashMap<(Ulid, Ulid), i64> {
+    if options.fresh_teams_weight == 0 {
+        return HashMap::new();
+    }
+
+    teammate_repeats(options.history)
+        .into_iter()
+        .map(|(pair, repeats)| (pair, repeats as i64 * options.fresh_teams_weight as i64))
+        .collect()
+}
+
+/// Extra rating each team needs for the slots it is short compared to the largest team.
+fn handicaps(players: &[Player], sizes: &[usize], options: &SplitOptions) -> Vec<u32> {
+    let largest_team = sizes.iter().copied().max().unwrap_or(0);
+    let total_rating: u32 = players.iter().map(|p| p.rating(options.attributes)).sum();
+    let average_rating = total_rating as f32 / players.len().max(1) as f32;
+    let per_slot = (average_rating * options.short_handed_bonus as f32 / 100.0).round() as u32;
+
+    sizes
+        .iter()
+        .map(|&size| (largest_team - size) as u32 * per_slot)
+        .collect()
+}
+
+/// Keeps the `limit` lowest-scoring distinct splits seen so far.
+struct SplitCandidates<'a> {
+    limit: usize,
+    objective: &'a Objective,
+    splits: Vec<Split>,
+    keys: Vec<Vec<Vec<Ulid>>>,
+}
+
+impl<'a> SplitCandidates<'a> {
+    fn new(limit: usize, objective: &'a Objective) -> Self {
+        SplitCandidates {
+            limit: limit.max(1),
+            objective,
+            splits: Vec::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.splits.len() >= self.limit
+    }
+
+    /// Score a new split has to beat to be kept, once the list is full.
+    fn threshold(&self) -> Option<i64> {
+        if self.is_full() {
+            self.splits.last().map(|split| split.score)
+        } else {
+            None
+        }
+    }
+
+    fn offer(&mut self, teams: Vec<Vec<Player>>) {
+        let score = self
+            .objective
+            .score(&teams, &self.objective.team_values(&teams));
+        if self.threshold().is_some_and(|worst| score >= worst) {
+            return;
+        }
+
+        let key = membership_key(&teams);
+        if self.keys.contains(&key) {
+            return;
+        }
+
+        let position = self.splits.partition_point(|split| split.score <= score);
+        self.splits.insert(
+            position,
+            Split {
+                teams,
+                score,
+                handicaps: self.objective.handicaps().to_vec(),
+            },
+        );
+        self.keys.insert(position, key);
+        self.splits.truncate(self.limit);
+        self.keys.truncate(self.limit);
+    }
+}
+
+fn membership_key(teams: &[Vec<Player>]) -> Vec<Vec<Ulid>> {
+    let mut key: Vec<Vec<Ulid>> = teams
+        .iter()
+        .map(|team| {
+            let mut members: Vec<Ulid> = team.iter().map(|p| p.id).collect();
+            members.sort();
+            members
+        })
+        .collect();
+    key.sort();
+    key
+}
+
+/// Sorts unit indices into the order they are placed in: pinned captains first, then the bigger
+/// groups while there is still room for them. Units of equal size keep their relative order.
+fn sort_for_placement(units: &[Unit], order: &mut [usize]) {
+    order.sort_by_key(|&idx| (units[idx].team.is_none(), Reverse(units[idx].players.len())));
+}
+
+fn create_greedy_teams(
+    units: &[Unit],
+    unit_values: &[Vec<i64>],
+    conflicts: &[Vec<bool>],
+    sizes: &[usize],
+    roles: &[Role],
+    objective: &Objective,
+    rng: &mut impl Rng,
+) -> Option<Vec<Vec<Player>>> {
+    let mut teams: Vec<Vec<Player>> = vec![Vec::new(); sizes.len()];
+    let mut team_units: Vec<Vec<usize>> = vec![Vec::new(); sizes.len()];
+    let mut values = objective.initial_values();
+
+    // Shuffle units for randomness instead of strict sorting
+    let mut order: Vec<usize> = (0..units.len()).collect();
+    order.shuffle(rng);
+    sort_for_placement(units, &mut order);
+
+    // Distribute units with randomization
+    for unit_idx in order {
+        let unit = &units[unit_idx];
+
+        let team_idx = if let Some(team_idx) = unit.team {
+            team_idx
+        } else {
+            // Get available teams (room left, nobody this unit must avoid and roles still fillable)
+            let available_teams: Vec<usize> = (0..sizes.len())
+                .filter(|&idx| teams[idx].len() + unit.players.len() <= sizes[idx])
+                .filter(|&idx| team_units[idx].iter().all(|&u| !conflicts[unit_idx][u]))
+                .filter(|&idx| {
+                    let joined = [teams[idx].as_slice(), unit.players.as_slice()].concat();
+                    fits_roles(&joined, sizes[idx], roles)
+                })
+                .collect();
+
+            if available_teams.is_empty() {
+                return None;
+            }
+
+            // Add some randomness: 50% of the time pick team with lowest rating,
+            // 50% of the time pick randomly from available teams
+            if rng.random::<f32>() < 0.5 && available_teams.len() > 1 {
+                // Pick team with lowest rating among available, handicap included
+                *available_teams
+                    .iter()
+                    .min_by_key(|&&idx| values[0][idx])
+                    .unwrap()
+            } else {
+                // Pick random available team
+                let random_idx = rng.random_range(0..available_teams.len());
+                available_teams[random_idx]
+            }
+        };
+
+        teams[team_idx].extend(unit.players.iter().cloned());
+        team_units[team_idx].push(unit_idx);
+        for (dimension, value) in unit_values[unit_idx].iter().enumerate() {
+            values[dimension][team_idx] += value;
+        }
+    }
+
+    // Pinned captains skip the role check while being placed
+    teams
+        .iter()
+        .zip(sizes)
+        .all(|(team, &size)| fits_roles(team, size, roles))
+        .then_some(teams)
+}
+
+/// Exhaustively searches every split of `units` and offers the most balanced ones to `best`.
+/// Branches that can no longer beat the kept splits are pruned.
+fn exact_split(
+    units: &[Unit],
+    unit_values: &[Vec<i64>],
+    conflicts: &[Vec<bool>],
+    sizes: &[usize],
+    roles: &[Role],
+    has_captains: bool,
+    best: &mut SplitCandidates,
+) {
+    let mut order: Vec<usize> = (0..units.len()).collect();
+    sort_for_placement(units, &mut order);
+
+    let values = best.objective.initial_values();
+    let totals: Vec<i64> = values
+        .iter()
+        .enumerate()
+        .map(|(dimension, start)| {
+            start.iter().sum::<i64>() + unit_values.iter().map(|v| v[dimension]).sum::<i64>()
+        })
+        .collect();
+
+    let mut search = ExactSearch {
+        units,
+        unit_values,
+        conflicts,
+        order,
+        sizes,
+        roles,
+        totals,
+        has_captains,
+        teams: vec![Vec::new(); sizes.len()],
+        team_units: vec![Vec::new(); sizes.len()],
+        values,
+        best,
+    };
+    search.assign(0);
+}
+
+struct ExactSearch<'a, 'b> {
+    units: &'a [Unit],
+    unit_values: &'a [Vec<i64>],
+    conflicts: &'a [Vec<bool>],
+    order: Vec<usize>,
+    sizes: &'a [usize],
+    roles: &'a [Role],
+    /// Final total of each dimension of `values` once every unit is placed.
+    totals: Vec<i64>,
+    has_captains: bool,
+    /// Teams being built.
+    teams: Vec<Vec<Player>>,
+    /// Indices into `units` placed on each team.
+    team_units: Vec<Vec<usize>>,
+    /// [`Objective`] values of `teams`.
+    values: Vec<Vec<i64>>,
+    best: &'a mut SplitCandidates<'b>,
+}
+
+impl ExactSearch<'_, '_> {
+    fn assign(&mut self, step: usize) {
+        if self.best.threshold() == Some(0) {
+            return;
+        }
+
+        if step == self.order.len() {
+            self.best.offer(self.teams.clone());
+            return;
+        }
+
+        if let Some(worst) = self.best.threshold()
+            && self
+                .best
+                .objective
+                .lower_bound(&self.teams, &self.values, &self.totals)
+                >= worst
+        {
+            return;
+        }
+
+        let unit_idx = self.order[step];
+        let unit = &self.units[unit_idx];
+        let mut tried_empty_sizes: Vec<usize> = Vec::new();
+
+        for team_idx in 0..self.teams.len() {
+            if unit.team.is_some_and(|pinned| pinned != team_idx)
+                || self.teams[team_idx].len() + unit.players.len() > self.sizes[team_idx]
+                || self.team_units[team_idx]
+                    .iter()
+                    .any(|&u| self.conflicts[unit_idx][u])
+            {
+                continue;
+            }
+
+            // Without captains, empty teams of the same size are interchangeable, so only one of
+            // them is tried. This also guarantees every split is visited exactly once.
+            if !self.has_captains && self.teams[team_idx].is_empty() {
+                if tried_empty_sizes.contains(&self.sizes[team_idx]) {
+                    continue;
+                }
+                tried_empty_sizes.push(self.sizes[team_idx]);
+            }
+
+            let len = self.teams[team_idx].len();
+            self.teams[team_idx].extend(unit.players.iter().cloned());
+            if !fits_roles(&self.teams[team_idx], self.sizes[team_idx], self.roles) {
+                self.teams[team_idx].truncate(len);
+                continue;
+            }
+
+            self.team_units[team_idx].push(unit_idx);
+            self.add_values(unit_idx, team_idx, 1);
+            self.assign(step + 1);
+            self.add_values(unit_idx, team_idx, -1);
+            self.team_units[team_idx].pop();
+            self.teams[team_idx].truncate(len);
+        }
+    }
+
+    fn add_values(&mut self, unit_idx: usize, team_idx: usize, sign: i64) {
+        for (dimension, value) in self.unit_values[unit_idx].iter().enumerate() {
+            self.values[dimension][team_idx] += sign * value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::Seed;
+
+    fn player(name: &str, skill: u32) -> Player {
+        Player {
+            name: name.to_owned(),
+            skill,
+            ..Default::default()
+        }
+    }
+
+    fn options<'a>(team_count: usize, metric: Metric) -> SplitOptions<'a> {
+        SplitOptions {
+            team_count,
+            team_sizes: &[],
+            short_handed_bonus: 0,
+            attributes: &[],
+            roles: &[],
+            constraints: &[],
+            history: &[],
+            fresh_teams_weight: 0,
+            metric,
+            candidates: 1,
+        }
+    }
+
+    fn random_roster(count: usize, rng: &mut impl Rng) -> Vec<Player> {
+        (0..count)
+            .map(|idx| player(&format!("P{}", idx), rng.random_range(0..3000)))
+            .collect()
+    }
+
+    /// Whether `teams` is a split the solver is allowed to return.
+    fn is_allowed(teams: &[Vec<Player>], sizes: &[usize], options: &SplitOptions) -> bool {
+        let team_of = |id: Ulid| {
+            teams
+                .iter()
+                .position(|team| team.iter().any(|p| p.id == id))
+        };
+        let captains = teams
+            .iter()
+            .map(|team| team.iter().filter(|p| p.is_captain).count())
+            .collect::<Vec<_>>();
+        let has_captains = captains.iter().sum::<usize>() == options.team_count;
+
+        teams
+            .iter()
+            .zip(sizes)
+            .all(|(team, &size)| team.len() == size)
+            && (!has_captains || captains.iter().all(|&count| count == 1))
+            && teams
+                .iter()
+                .zip(sizes)
+                .all(|(team, &size)| fits_roles(team, size, options.roles))
+            && options.constraints.iter().all(|constraint| {
+                let teams: Vec<Option<usize>> =
+                    constraint.players.iter().map(|&id| team_of(id)).collect();
+                match constraint.kind {
+                    ConstraintKind::Together => teams.windows(2).all(|pair| pair[0] == pair[1]),
+                    ConstraintKind::Apart => teams
+                        .iter()
+                        .enumerate()
+                        .all(|(idx, team)| !teams[idx + 1..].contains(team)),
+                }
+            })
+    }
+
+    /// Lowest score of every allowed split, found by trying each assignment of players to teams.
+    fn brute_force_score(players: &[Player], options: &SplitOptions) -> Option<i64> {
+        let team_count = options.team_count;
+        let sizes = team_sizes(players.len(), options).unwrap();
+        let objective = Objective::new(
+            players,
+            options.attributes,
+            handicaps(players, &sizes, options),
+            teammate_penalties(options),
+            options.metric,
+        );
+
+        (0..team_count.pow(players.len() as u32))
+            .filter_map(|mut code| {
+                let mut teams = vec![Vec::new(); team_count];
+                for player in players {
+                    teams[code % team_count].push(player.clone());
+                    code /= team_count;
+                }
+                is_allowed(&teams, &sizes, options)
+                    .then(|| objective.score(&teams, &objective.team_values(&teams)))
+            })
+            .min()
+    }
+
+    fn solver_score(players: &[Player], options: &SplitOptions) -> Option<i64> {
+        let mut players = players.to_vec();
+        best_balanced_split(&mut players, options, &mut Seed(1).rng())
+            .ok()
+            .map(|splits| splits[0].score)
+    }
+
+    #[test]
+    fn exact_solver_matches_brute_force_for_every_metric() {
+        let mut rng = Seed(7).rng();
+        for metric in Metric::ALL {
+            for _ in 0..15 {
+                let team_count = rng.random_range(2..=3);
+                let players = random_roster(rng.random_range(team_count..=9), &mut rng);
+                let options = options(team_count, metric);
+                assert_eq!(
+                    solver_score(&players, &options),
+                    brute_force_score(&players, &options),
+                    "{:?} with {} players in {} teams",
+                    metric,
+                    players.len(),
+                    team_count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exact_solver_matches_brute_force_with_attributes_and_uneven_sizes() {
+        let attribute = Attribute::new("AWP".to_owned());
+        let attributes = [attribute.clone()];
+        let mut rng = Seed(11).rng();
+        for metric in Metric::ALL {
+            let mut players = random_roster(7, &mut rng);
+            for player in &mut players {
+                player
+                    .attributes
+                    .insert(attribute.id, rng.random_range(0..500));
+            }
+            let options = SplitOptions {
+                team_sizes: &[4, 3],
+                short_handed_bonus: 50,
+                attributes: &attributes,
+                ..options(2, metric)
+            };
+            assert_eq!(
+                solver_score(&players, &options),
+                brute_force_score(&players, &options),
+                "{:?}",
+                metric
+            );
+        }
+    }
+
+    #[test]
+    fn exact_solver_matches_brute_force_with_captains_constraints_and_roles() {
+        let role = Role::new("Support".to_owned());
+        let roles = [role.clone()];
+        let mut rng = Seed(3).rng();
+        for metric in Metric::ALL {
+            let mut players = random_roster(8, &mut rng);
+            players[0].is_captain = true;
+            players[1].is_captain = true;
+            for player in &mut players[4..] {
+                player.roles.insert(role.id);
+            }
+            let constraints = [
+                Constraint {
+                    kind: ConstraintKind::Together,
+                    players: vec![players[2].id, players[3].id],
+                },
+                Constraint {
+                    kind: ConstraintKind::Apart,
+                    players: vec![players[4].id, players[5].id],
+                },
+            ];
+            let options = SplitOptions {
+                roles: &roles,
+                constraints: &constraints,
+                ..options(2, metric)
+            };
+            assert_eq!(
+                solver_score(&players, &options),
+                brute_force_score(&players, &options),
+                "{:?}",
+                metric
+            );
+        }
+    }
+
+    #[test]
+    fn captains_lead_different_teams() {
+        let mut players = random_roster(8, &mut Seed(5).rng());
+        players[0].is_captain = true;
+        players[1].is_captain = true;
+        let splits = best_balanced_split(
+            &mut players,
+            &options(2, Metric::Variance),
+            &mut Seed(2).rng(),
+        )
+        .unwrap();
+        for team in &splits[0].teams {
+            assert_eq!(team.iter().filter(|p| p.is_captain).count(), 1);
+        }
+    }
+
+    #[test]
+    fn constraints_are_kept() {
+        let mut players = random_roster(10, &mut Seed(9).rng());
+        let constraints = [
+            Constraint {
+                kind: ConstraintKind::Together,
+                players: vec![players[0].id, players[1].id, players[2].id],
+            },
+            Constraint {
+                kind: ConstraintKind::Apart,
+                players: vec![players[3].id, players[4].id],
+            },
+        ];
+        let options = SplitOptions {
+            constraints: &constraints,
+            ..options(2, Metric::Variance)
+        };
+        let ids: Vec<Ulid> = players.iter().map(|p| p.id).collect();
+        let splits = best_balanced_split(&mut players, &options, &mut Seed(4).rng()).unwrap();
+        let team_of = |id: Ulid| {
+            splits[0]
+                .teams
+                .iter()
+                .position(|team| team.iter().any(|p| p.id == id))
+        };
+        assert_eq!(team_of(ids[0]), team_of(ids[1]));
+        assert_eq!(team_of(ids[1]), team_of(ids[2]));
+        assert_ne!(team_of(ids[3]), team_of(ids[4]));
+    }
+
+    #[test]
+    fn uneven_sizes_are_followed() {
+        let mut players = random_roster(9, &mut Seed(6).rng());
+        let options = SplitOptions {
+            team_sizes: &[2, 3, 4],
+            ..options(3, Metric::Spread)
+        };
+        let splits = best_balanced_split(&mut players, &options, &mut Seed(8).rng()).unwrap();
+        let sizes: Vec<usize> = splits[0].teams.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [2, 3, 4]);
+
+        let options = SplitOptions {
+            team_sizes: &[2, 3],
+            ..options
+        };
+        assert!(matches!(
+            best_balanced_split(&mut players, &options, &mut Seed(8).rng()),
+            Err(SplitError::TeamSizesMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn splits_are_reproducible_from_the_seed() {
+        let players = random_roster(20, &mut Seed(12).rng());
+        let options = options(3, Metric::Variance);
+        let split = |seed: Seed, mut players: Vec<Player>| {
+            let splits = best_balanced_split(&mut players, &options, &mut seed.rng()).unwrap();
+            membership_key(&splits[0].teams)
+        };
+        let mut reversed = players.clone();
+        reversed.reverse();
+        assert_eq!(split(Seed(1), players.clone()), split(Seed(1), reversed));
+    }
+
+    #[test]
+    fn bench_picks_whoever_sat_out_longest_ago() {
+        let mut players = random_roster(5, &mut Seed(13).rng());
+        for (idx, player) in players.iter_mut().enumerate() {
+            player.last_benched = Some(idx as u32);
+        }
+        players[0].is_captain = true;
+        let (playing, bench) = pick_bench(&players, 2, &mut Seed(1).rng());
+        assert_eq!(playing.len(), 3);
+        let benched: Vec<&str> = bench.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(benched, ["P1", "P2"]);
+    }
+}
@@ -0,0 +1,144 @@
+use super::history::pair_key;
+use super::metric::Metric;
+use super::{Attribute, Player};
+use std::collections::HashMap;
+use ulid::Ulid;
+
+/// Measures how unbalanced a split is, lower is better.
+///
+/// Teams are compared along several dimensions: dimension 0 is the combined rating (skill plus
+/// weighted attributes, minus the team's handicap) and dimension `i + 1` is the total of the i-th
+/// attribute. Values are stored dimension-major, as `values[dimension][team]`.
+///
+/// Each dimension is scored by the chosen [`Metric`], which may also penalize how the best
+/// players are spread. On top of that, every pair of teammates adds its entry from
+/// `teammate_penalties`, which keeps the same people from ending up together game after game.
+pub(super) struct Objective {
+    attributes: Vec<Attribute>,
+    handicaps: Vec<u32>,
+    teammate_penalties: HashMap<(Ulid, Ulid), i64>,
+    metric: Metric,
+    /// Rating from which a player counts as one of the top players.
+    top_threshold: i64,
+}
+
+impl Objective {
+    pub fn new(
+        players: &[Player],
+        attributes: &[Attribute],
+        handicaps: Vec<u32>,
+        teammate_penalties: HashMap<(Ulid, Ulid), i64>,
+        metric: Metric,
+    ) -> Self {
+        let mut ratings: Vec<i64> = players
+            .iter()
+            .map(|p| p.rating(attributes) as i64)
+            .collect();
+        ratings.sort_unstable_by(|a, b| b.cmp(a));
+        let top_threshold = ratings
+            .get(handicaps.len().saturating_sub(1))
+            .copied()
+            .unwrap_or(0);
+
+        Objective {
+            attributes: attributes.to_vec(),
+            handicaps,
+            teammate_penalties,
+            metric,
+            top_threshold,
+        }
+    }
+
+    pub fn handicaps(&self) -> &[u32] {
+        &self.handicaps
+    }
+
+    /// What a group of players adds to their team in each dimension.
+    pub fn group_values(&self, players: &[Player]) -> Vec<i64> {
+        let mut values = vec![0; 1 + self.attributes.len()];
+        for player in players {
+            values[0] += player.rating(&self.attributes) as i64;
+            for (idx, attribute) in self.attributes.iter().enumerate() {
+                values[idx + 1] += player.attribute(attribute.id) as i64;
+            }
+        }
+        values
+    }
+
+    /// Values of empty teams. Handicaps count against a team's rating, so short-handed teams
+    /// start below zero.
+    pub fn initial_values(&self) -> Vec<Vec<i64>> {
+        let mut values = vec![vec![0; self.handicaps.len()]; 1 + self.attributes.len()];
+        for (team_idx, &handicap) in self.handicaps.iter().enumerate() {
+            values[0][team_idx] = -(handicap as i64);
+        }
+        values
+    }
+
+    pub fn team_values(&self, teams: &[Vec<Player>]) -> Vec<Vec<i64>> {
+        let mut values = self.initial_values();
+        for (team_idx, team) in teams.iter().enumerate() {
+            for (dimension, value) in self.group_values(team).into_iter().enumerate() {
+                values[dimension][team_idx] += value;
+            }
+        }
+        values
+    }
+
+    pub fn score(&self, teams: &[Vec<Player>], values: &[Vec<i64>]) -> i64 {
+        self.combine(|dimension| self.metric.score(&values[dimension]))
+            + self.player_penalty(teams, true)
+            + self.teammate_penalty(teams)
+    }
+
+    /// Smallest [`Objective::score`] any completion of the partial `teams` and their `values` can
+    /// reach, given the final total of each dimension. Values and teammate penalties only ever
+    /// grow while the remaining players are placed.
+    pub fn lower_bound(&self, teams: &[Vec<Player>], values: &[Vec<i64>], totals: &[i64]) -> i64 {
+        self.combine(|dimension| {
+            self.metric
+                .lower_bound(&values[dimension], totals[dimension])
+        }) + self.player_penalty(teams, false)
+            + self.teammate_penalty(teams)
+    }
+
+    fn player_penalty(&self, teams: &[Vec<Player>], complete: bool) -> i64 {
+        self.metric.player_penalty(
+            teams,
+            |p| p.rating(&self.attributes) as i64,
+            self.top_threshold,
+            complete,
+        )
+    }
+
+    fn teammate_penalty(&self, teams: &[Vec<Player>]) -> i64 {
+        if self.teammate_penalties.is_empty() {
+            return 0;
+        }
+
+        let mut penalty = 0;
+        for team in teams {
+            for (idx, a) in team.iter().enumerate() {
+                for b in &team[idx + 1..] {
+                    penalty += self
+                        .teammate_penalties
+                        .get(&pair_key(a.id, b.id))
+                        .copied()
+                        .unwrap_or(0);
+                }
+            }
+        }
+        penalty
+    }
+
+    /// The rating imbalance, plus each attribute's imbalance scaled by its weight.
+    fn combine(&self, per_dimension: impl Fn(usize) -> i64) -> i64 {
+        per_dimension(0)
+            + self
+                .attributes
+                .iter()
+                .enumerate()
+                .map(|(idx, attribute)| per_dimension(idx + 1) * attribute.weight as i64 / 100)
+                .sum::<i64>()
+    }
+}
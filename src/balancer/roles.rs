@@ -0,0 +1,153 @@
+use super::Player;
+use std::collections::BTreeMap;
+use ulid::Ulid;
+
+/// A position players can be tagged with, like "AWP" or "Goalkeeper". Every team needs
+/// `per_team` players playing it.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Role {
+    pub id: Ulid,
+    pub name: String,
+    pub per_team: usize,
+}
+
+impl Role {
+    /// A new role needing one player per team.
+    pub fn new(name: String) -> Self {
+        Role {
+            id: Ulid::new(),
+            name,
+            per_team: 1,
+        }
+    }
+}
+
+/// Assigns players of `team` to the role slots it needs, returning player id to role id.
+/// Players left out of the map play no particular role.
+pub fn assign_roles(team: &[Player], roles: &[Role]) -> BTreeMap<Ulid, Ulid> {
+    let slots = slots(roles);
+    match_slots(team, roles, &slots)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(slot, player)| player.map(|p| (team[p].id, roles[slots[slot]].id)))
+        .collect()
+}
+
+/// Number of role slots `team` cannot fill with its current players.
+pub fn unfilled_slots(team: &[Player], roles: &[Role]) -> usize {
+    let slots = slots(roles);
+    match_slots(team, roles, &slots)
+        .iter()
+        .filter(|player| player.is_none())
+        .count()
+}
+
+/// One entry per slot a team has to fill, holding the index of the slot's role.
+fn slots(roles: &[Role]) -> Vec<usize> {
+    roles
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, role)| std::iter::repeat_n(idx, role.per_team))
+        .collect()
+}
+
+/// Maximum bipartite matching between slots and the players able to play them, returning the
+/// index into `team` filling each slot.
+fn match_slots(team: &[Player], roles: &[Role], slots: &[usize]) -> Vec<Option<usize>> {
+    let mut slot_of_player: Vec<Option<usize>> = vec![None; team.len()];
+    let mut player_of_slot: Vec<Option<usize>> = vec![None; slots.len()];
+
+    for slot in 0..slots.len() {
+        let mut visited = vec![false; team.len()];
+        augment(
+            slot,
+            team,
+            roles,
+            slots,
+            &mut visited,
+            &mut slot_of_player,
+            &mut player_of_slot,
+        );
+    }
+
+    player_of_slot
+}
+
+fn augment(
+    slot: usize,
+    team: &[Player],
+    roles: &[Role],
+    slots: &[usize],
+    visited: &mut [bool],
+    slot_of_player: &mut [Option<usize>],
+    player_of_slot: &mut [Option<usize>],
+) -> bool {
+    let role = &roles[slots[slot]];
+
+    for (player_idx, player) in team.iter().enumerate() {
+        if visited[player_idx] || !player.roles.contains(&role.id) {
+            continue;
+        }
+        visited[player_idx] = true;
+
+        let free = match slot_of_player[player_idx] {
+            None => true,
+            Some(other_slot) => augment(
+                other_slot,
+                team,
+                roles,
+                slots,
+                visited,
+                slot_of_player,
+                player_of_slot,
+            ),
+        };
+
+        if free {
+            slot_of_player[player_idx] = Some(slot);
+            player_of_slot[slot] = Some(player_idx);
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(roles: &[&Role]) -> Player {
+        Player {
+            roles: roles.iter().map(|r| r.id).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn slots_are_matched_even_when_the_first_choice_is_wrong() {
+        let entry = Role::new("Entry".to_owned());
+        let awp = Role::new("AWP".to_owned());
+        let roles = [entry.clone(), awp.clone()];
+        // The flexible player has to give the entry slot up to the one who can only play entry
+        let team = [player(&[&entry, &awp]), player(&[&entry]), player(&[])];
+
+        assert_eq!(unfilled_slots(&team, &roles), 0);
+        let assigned = assign_roles(&team, &roles);
+        assert_eq!(assigned.get(&team[0].id), Some(&awp.id));
+        assert_eq!(assigned.get(&team[1].id), Some(&entry.id));
+        assert_eq!(assigned.get(&team[2].id), None);
+    }
+
+    #[test]
+    fn missing_players_leave_slots_unfilled() {
+        let mut support = Role::new("Support".to_owned());
+        support.per_team = 2;
+        let roles = [support.clone()];
+        let team = [player(&[&support]), player(&[])];
+
+        assert_eq!(unfilled_slots(&team, &roles), 1);
+        assert_eq!(unfilled_slots(&[], &roles), 2);
+        assert_eq!(unfilled_slots(&team, &[]), 0);
+    }
+}
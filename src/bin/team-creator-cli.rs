@@ -0,0 +1,336 @@
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::process::ExitCode;
+use team_creator::balancer::{
+    Attribute, Constraint, ConstraintKind, MAX_SKILL, Metric, PastGame, Player, Role, SplitOptions,
+    best_balanced_split, pick_bench,
+};
+use team_creator::export::{self, ExportFormat, ExportOptions};
+use team_creator::rating::{MatchRecord, RatingModel, Ratings};
+use team_creator::save;
+use team_creator::seed::Seed;
+use ulid::Ulid;
+
+const USAGE: &str = "\
+Usage: team-creator-cli [OPTIONS] [ROSTER]
+
+Splits a roster into balanced teams and prints them.
+
+ROSTER is a file in the Team Creator's save format, or `-` to read it from
+standard input. Defaults to the app's team_creator_cache.ron. The file is
+only read, never changed. Players marked absent in the app are left out.
+
+Options:
+  -t, --teams <N>           Number of teams [default: the roster's]
+      --sizes <N,N,...>     Players on each team, extra players are benched
+      --together <A,B,...>  Keep these players on the same team, by name
+      --apart <A,B,...>     Keep these players on different teams, by name
+  -m, --metric <METRIC>     variance, spread, absolute-deviation,
+                            strongest-player or top-heavy [default: the roster's]
+  -s, --seed <SEED>         Seed of earlier teams to create them again
+  -f, --format <FORMAT>     text, markdown, code-block, json, csv or template
+                            [default: text]
+      --template <TEMPLATE> Layout of each team for the template format, with
+                            {team}, {number}, {skill}, {size}, {captain},
+                            {players} and {list} filled in
+                            [default: \"{team} ({skill}): {players}\"]
+  -h, --help                Print this help
+";
+
+/// The parts of the Team Creator's save file used to create teams.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Roster {
+    players: Vec<Player>,
+    #[serde(default)]
+    absent: BTreeSet<Ulid>,
+    #[serde(default = "default_number_of_teams")]
+    number_of_teams: usize,
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+    #[serde(default)]
+    team_sizes: Vec<usize>,
+    #[serde(default = "default_short_handed_bonus")]
+    short_handed_bonus: u32,
+    #[serde(default)]
+    players_per_team: Option<usize>,
+    #[serde(default)]
+    attributes: Vec<Attribute>,
+    #[serde(default)]
+    roles: Vec<Role>,
+    #[serde(default)]
+    history: Vec<PastGame>,
+    #[serde(default)]
+    fresh_teams_weight: u32,
+    #[serde(default)]
+    metric: Metric,
+    #[serde(default)]
+    matches: Vec<MatchRecord>,
+    #[serde(default)]
+    rating_model: RatingModel,
+    #[serde(default)]
+    use_learned_ratings: bool,
+}
+
+fn default_number_of_teams() -> usize {
+    2
+}
+
+fn default_short_handed_bonus() -> u32 {
+    50
+}
+
+struct Args {
+    roster: String,
+    teams: Option<usize>,
+    sizes: Option<Vec<usize>>,
+    constraints: Vec<(ConstraintKind, Vec<String>)>,
+    metric: Option<Metric>,
+    seed: Seed,
+    format: ExportFormat,
+    template: String,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args) {
+        Ok(output) => {
+            print!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parses the command line, or returns `None` when help was asked for.
+fn parse_args(mut raw: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut args = Args {
+        roster: "team_creator_cache.ron".to_owned(),
+        teams: None,
+        sizes: None,
+        constraints: Vec::new(),
+        metric: None,
+        seed: Seed::random(),
+        format: ExportFormat::Text,
+        template: export::DEFAULT_TEMPLATE.to_owned(),
+    };
+
+    while let Some(arg) = raw.next() {
+        let mut value = || raw.next().ok_or(format!("missing value for `{}`", arg));
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-t" | "--teams" => {
+                let teams = value()?;
+                args.teams = Some(parse_number(&teams)?);
+            }
+            "--sizes" => {
+                let sizes = value()?;
+                args.sizes = Some(
+                    sizes
+                        .split(',')
+                        .map(parse_number)
+                        .collect::<Result<_, _>>()?,
+                );
+            }
+            "--together" => args
+                .constraints
+                .push((ConstraintKind::Together, split_names(&value()?))),
+            "--apart" => args
+                .constraints
+                .push((ConstraintKind::Apart, split_names(&value()?))),
+            "-m" | "--metric" => {
+                let metric = value()?;
+                args.metric = Some(
+                    Metric::ALL
+                        .into_iter()
+                        .find(|m| m.label().to_lowercase().replace(' ', "-") == metric)
+                        .ok_or(format!("unknown metric `{}`", metric))?,
+                );
+            }
+            "-s" | "--seed" => {
+                let seed = value()?;
+                args.seed = seed
+                    .parse()
+                    .map_err(|_| format!("invalid seed `{}`", seed))?;
+            }
+            "-f" | "--format" => {
+                let format = value()?;
+                args.format = ExportFormat::ALL
+                    .into_iter()
+                    .find(|f| {
+                        f.name() == format || (format == "md" && *f == ExportFormat::Markdown)
+                    })
+                    .ok_or(format!("unknown format `{}`", format))?;
+            }
+            "--template" => {
+                args.template = value()?;
+                args.format = ExportFormat::Template;
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option `{}`", arg));
+            }
+            _ => args.roster = arg,
+        }
+    }
+
+    Ok(Some(args))
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{}` is not a number", value))
+}
+
+fn split_names(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|name| name.trim().to_owned())
+        .collect()
+}
+
+fn run(args: Args) -> Result<String, String> {
+    let mut roster = read_roster(&args.roster)?;
+    // Only the players present in the app's current session play
+    roster.players.retain(|p| !roster.absent.contains(&p.id));
+
+    if let Some(teams) = args.teams {
+        roster.number_of_teams = teams;
+        roster.team_sizes.clear();
+    }
+    if let Some(sizes) = args.sizes {
+        roster.number_of_teams = sizes.len();
+        roster.team_sizes = sizes;
+        roster.players_per_team = None;
+    }
+    if let Some(metric) = args.metric {
+        roster.metric = metric;
+    }
+    for (kind, names) in args.constraints {
+        let players = names
+            .iter()
+            .map(|name| find_player(&roster.players, name))
+            .collect::<Result<_, _>>()?;
+        roster.constraints.push(Constraint { kind, players });
+    }
+
+    if roster.number_of_teams == 0 {
+        return Err("need at least one team".to_owned());
+    }
+    if roster.players.len() < roster.number_of_teams {
+        return Err(format!(
+            "{} players are not enough for {} teams",
+            roster.players.len(),
+            roster.number_of_teams
+        ));
+    }
+
+    // Bench whoever doesn't fit, the same way the app does
+    let slots = if !roster.team_sizes.is_empty() {
+        roster.team_sizes.iter().sum()
+    } else if let Some(players_per_team) = roster.players_per_team {
+        players_per_team * roster.number_of_teams
+    } else {
+        roster.players.len()
+    };
+    let bench_size = roster.players.len().saturating_sub(slots);
+    let (mut playing, bench) = pick_bench(&roster.players, bench_size, &mut args.seed.rng());
+
+    if roster.use_learned_ratings {
+        let ratings = Ratings::from_matches(roster.rating_model, &roster.matches);
+        for player in &mut playing {
+            if let Some(rating) = ratings.get(player.id) {
+                player.skill = rating.skill();
+            }
+        }
+    }
+
+    let options = SplitOptions {
+        team_count: roster.number_of_teams,
+        team_sizes: &roster.team_sizes,
+        short_handed_bonus: roster.short_handed_bonus,
+        attributes: &roster.attributes,
+        roles: &roster.roles,
+        constraints: &roster.constraints,
+        history: &roster.history,
+        fresh_teams_weight: roster.fresh_teams_weight,
+        metric: roster.metric,
+        candidates: 1,
+    };
+    let splits = best_balanced_split(&mut playing, &options, &mut args.seed.rng())
+        .map_err(|error| error.to_string())?;
+    let teams = &splits[0].teams;
+    let hide_skills = roster.players.iter().all(|p| p.skill == 0);
+
+    let options = ExportOptions {
+        attributes: &roster.attributes,
+        roles: &roster.roles,
+        hide_skills,
+        template: &args.template,
+    };
+    let output = export::export(args.format, teams, &bench, &options);
+
+    // The seed goes along wherever it doesn't break the format
+    Ok(match args.format {
+        ExportFormat::Text | ExportFormat::CodeBlock => {
+            format!("{}\n\nSeed: {}\n", output.trim_end(), args.seed)
+        }
+        ExportFormat::Markdown => format!("{}\n\nSeed: `{}`\n", output.trim_end(), args.seed),
+        ExportFormat::Json => {
+            let mut output: serde_json::Value =
+                serde_json::from_str(&output).map_err(|e| e.to_string())?;
+            output["seed"] = args.seed.to_string().into();
+            format!("{:#}\n", output)
+        }
+        ExportFormat::Csv | ExportFormat::Template => output,
+    })
+}
+
+fn read_roster(path: &str) -> Result<Roster, String> {
+    let contents = if path == "-" {
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .map_err(|e| format!("failed to read standard input: {}", e))?;
+        contents
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?
+    };
+
+    // Upgraded like the app does, reading only the fields needed here
+    let roster: Roster = save::from_str(&contents, save::team_creator_migrations::<Roster>())
+        .map_err(|e| format!("failed to read {}: {}", path, e))?;
+
+    let too_high =
+        |p: &&Player| p.skill > MAX_SKILL || p.attributes.values().any(|&v| v > MAX_SKILL);
+    if let Some(player) = roster.players.iter().find(too_high) {
+        return Err(format!(
+            "{} has a skill or attribute over {}",
+            player.name, MAX_SKILL
+        ));
+    }
+    Ok(roster)
+}
+
+fn find_player(players: &[Player], name: &str) -> Result<Ulid, String> {
+    players
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+        .map(|p| p.id)
+        .ok_or(format!("no player named `{}`", name))
+}
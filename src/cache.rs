@@ -1,57 +1,136 @@
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use team_creator::save::{self, LoadError, Migration};
+
+/// Saved data that couldn't be read since the app started, for the app to show.
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
 pub trait PersistentCache: Sized + Serialize + for<'de> Deserialize<'de> {
     fn filename() -> &'static str;
+
+    /// Steps upgrading files saved in older formats, see [`save::from_str`]. Add one whenever the
+    /// struct changes in a way that files saved before can't be read anymore.
+    fn migrations() -> &'static [Migration] {
+        &[]
+    }
+
     fn save_to_disk(&self)
     where
         Self: Serialize,
     {
-        let ron_string = ron::to_string(&self).expect("Failed to serialize data to RON");
+        let version = save::current_version(Self::migrations());
+        let ron_string = save::to_string(self, version).expect("Failed to serialize data to RON");
+
+        write_text(Self::filename(), &ron_string).expect("Failed to write cache");
+    }
+
+    /// The saved data, upgraded to the current format, or `None` if nothing was saved.
+    fn load() -> Result<Option<Self>, LoadError> {
+        let Some(bytes) = read_bytes(Self::filename()) else {
+            return Ok(None);
+        };
+        let text = String::from_utf8(bytes)
+            .map_err(|_| LoadError::Invalid("it is not UTF-8 text".to_owned()))?;
+        save::from_str(&text, Self::migrations()).map(Some)
+    }
+
+    /// The saved data, or `init` if there is none. Saved data that can't be read is kept aside
+    /// as a `.bak` and a warning is left for the app to show.
+    fn read_or(init: Self) -> Self {
+        match Self::load() {
+            Ok(data) => data.unwrap_or(init),
+            Err(error) => {
+                warn_unreadable(Self::filename(), &error);
+                init
+            }
+        }
+    }
+}
+
+/// Takes the warnings about saved data that couldn't be read.
+pub fn take_warnings() -> Vec<String> {
+    std::mem::take(&mut *WARNINGS.lock().unwrap_or_else(|e| e.into_inner()))
+}
 
-        #[cfg(not(target_arch = "wasm32"))]
-        std::fs::write(Self::filename(), ron_string).expect("Failed to write cache to disk");
+/// Keeps the unreadable contents of `filename` aside, byte for byte, so saving over it loses
+/// nothing.
+fn warn_unreadable(filename: &str, error: &LoadError) {
+    let backup = (1..)
+        .map(|n| match n {
+            1 => format!("{}.bak", filename),
+            n => format!("{}.{}.bak", filename, n),
+        })
+        .find(|name| read_bytes(name).is_none())
+        .filter(|backup| copy(filename, backup).is_ok());
+
+    let kept = match backup {
+        Some(backup) => format!("The old data was kept as {}.", backup),
+        None => "The old data could not be kept.".to_owned(),
+    };
+    WARNINGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(format!(
+            "{} could not be read, {}. Starting over without it. {}",
+            filename, error, kept
+        ));
+}
+
+/// Contents of a file in the working directory, or of an entry of localStorage on the web.
+fn read_bytes(name: &str) -> Option<Vec<u8>> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::read(name).ok()
+    }
 
-        #[cfg(target_arch = "wasm32")]
-        if let Some(storage) = web_sys::window()
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = web_sys::window()
             .expect("no global `window` exists")
             .local_storage()
             .ok()
+            .flatten()?;
+        storage
+            .get_item(name)
+            .ok()
             .flatten()
-        {
-            storage
-                .set_item(Self::filename(), &ron_string)
-                .expect("Failed to write cache to localStorage");
-        }
+            .map(String::into_bytes)
     }
+}
 
-    fn read_from_disk() -> Option<Self>
-    where
-        Self: Sized,
+fn copy(from: &str, to: &str) -> Result<(), String> {
+    #[cfg(not(target_arch = "wasm32"))]
     {
-        #[cfg(not(target_arch = "wasm32"))]
-        let ron_str = {
-            let appdata = std::fs::read(Self::filename()).ok()?;
-            String::from_utf8(appdata).ok()?
-        };
+        std::fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
 
-        #[cfg(target_arch = "wasm32")]
-        let ron_str = {
-            if let Some(storage) = web_sys::window()
-                .expect("no global `window` exists")
-                .local_storage()
-                .ok()
-                .flatten()
-            {
-                storage.get_item(Self::filename()).ok().flatten()?
-            } else {
-                return None;
-            }
-        };
+    // localStorage only holds text, so the entry is copied as it is
+    #[cfg(target_arch = "wasm32")]
+    {
+        let bytes = read_bytes(from).ok_or_else(|| format!("{} is gone", from))?;
+        let text = String::from_utf8(bytes).expect("localStorage entries are strings");
+        write_text(to, &text)
+    }
+}
 
-        ron::from_str(&ron_str).ok()
+fn write_text(name: &str, text: &str) -> Result<(), String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::write(name, text).map_err(|e| e.to_string())
     }
 
-    fn read_or(init: Self) -> Self {
-        Self::read_from_disk().unwrap_or(init)
+    #[cfg(target_arch = "wasm32")]
+    {
+        match web_sys::window()
+            .expect("no global `window` exists")
+            .local_storage()
+            .ok()
+            .flatten()
+        {
+            Some(storage) => storage.set_item(name, text).map_err(|e| format!("{:?}", e)),
+            None => Ok(()),
+        }
     }
 }
@@ -0,0 +1,53 @@
+/// Saves `bytes` as a file named `filename`: in the working directory on native, or as a
+/// browser download on the web. Returns a message saying where it went.
+pub fn save_file(filename: &str, bytes: &[u8], mime_type: &str) -> Result<String, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = mime_type;
+        std::fs::write(filename, bytes)
+            .map(|_| format!("Saved to {}", filename))
+            .map_err(|error| format!("Failed to write {}: {}", filename, error))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        use eframe::wasm_bindgen::closure::Closure;
+        use eframe::wasm_bindgen::{JsCast as _, JsValue};
+
+        /// Time the browser gets to start the download before its data is released.
+        const REVOKE_DELAY_MS: i32 = 60_000;
+
+        let fail = |error: JsValue| format!("Failed to download {}: {:?}", filename, error);
+
+        let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime_type);
+        let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+            .map_err(fail)?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(fail)?;
+
+        let window = web_sys::window().ok_or_else(|| "No window to download from".to_owned())?;
+        let anchor = window
+            .document()
+            .ok_or_else(|| "No document to download from".to_owned())?
+            .create_element("a")
+            .map_err(fail)?
+            .dyn_into::<web_sys::HtmlAnchorElement>()
+            .map_err(|element| fail(element.into()))?;
+        anchor.set_href(&url);
+        anchor.set_download(filename);
+        anchor.click();
+
+        // Revoking the URL right away can cancel the download in some browsers
+        let revoke = Closure::once_into_js(move || {
+            let _ = web_sys::Url::revoke_object_url(&url);
+        });
+        window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                revoke.unchecked_ref(),
+                REVOKE_DELAY_MS,
+            )
+            .map_err(fail)?;
+        Ok(format!("Downloaded {}", filename))
+    }
+}
@@ -0,0 +1,263 @@
+//! Captains taking turns picking their teams.
+
+use crate::balancer::Player;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use ulid::Ulid;
+
+/// In which order captains pick within each round.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DraftOrder {
+    /// Every other round is picked in reverse, so the last captain of a round also picks first
+    /// in the next one.
+    #[default]
+    Snake,
+    /// Every round is picked in the same order.
+    Linear,
+    /// Every round is picked in a new random order.
+    Random,
+}
+
+impl DraftOrder {
+    pub const ALL: [DraftOrder; 3] = [DraftOrder::Snake, DraftOrder::Linear, DraftOrder::Random];
+
+    /// Short name, for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            DraftOrder::Snake => "🐍 Snake",
+            DraftOrder::Linear => "➡ Linear",
+            DraftOrder::Random => "🎲 Random",
+        }
+    }
+}
+
+/// A player picked by a team's captain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pick {
+    /// Round of the pick, starting at zero.
+    pub round: usize,
+    pub team: usize,
+    pub player: Player,
+}
+
+/// A draft in progress. Each team starts with its captain, the rest of the players are picked
+/// one at a time until the pool is empty.
+#[derive(Clone, Debug)]
+pub struct Draft {
+    /// Team picking at each turn, decided up front.
+    turns: Vec<usize>,
+    teams: Vec<Vec<Player>>,
+    pool: Vec<Player>,
+    picks: Vec<Pick>,
+}
+
+impl Draft {
+    /// Starts a draft with one team per captain. `rng` only matters for [`DraftOrder::Random`].
+    pub fn new(
+        captains: Vec<Player>,
+        pool: Vec<Player>,
+        order: DraftOrder,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let team_count = captains.len();
+        let rounds = pool.len().div_ceil(team_count.max(1));
+
+        let mut turns = Vec::with_capacity(rounds * team_count);
+        for round in 0..rounds {
+            let mut round_order: Vec<usize> = (0..team_count).collect();
+            match order {
+                DraftOrder::Snake if round % 2 == 1 => round_order.reverse(),
+                DraftOrder::Random => round_order.shuffle(rng),
+                _ => {}
+            }
+            turns.extend(round_order);
+        }
+        turns.truncate(pool.len());
+
+        Draft {
+            turns,
+            teams: captains.into_iter().map(|captain| vec![captain]).collect(),
+            pool,
+            picks: Vec::new(),
+        }
+    }
+
+    pub fn teams(&self) -> &[Vec<Player>] {
+        &self.teams
+    }
+
+    /// Players nobody has picked yet.
+    pub fn pool(&self) -> &[Player] {
+        &self.pool
+    }
+
+    /// Picks made so far, in order.
+    pub fn picks(&self) -> &[Pick] {
+        &self.picks
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    /// Team whose captain picks next, `None` once the draft is finished.
+    pub fn current_team(&self) -> Option<usize> {
+        if self.is_finished() {
+            None
+        } else {
+            self.turns.get(self.picks.len()).copied()
+        }
+    }
+
+    /// Round of the next pick, starting at zero.
+    pub fn round(&self) -> usize {
+        self.picks.len() / self.teams.len().max(1)
+    }
+
+    /// The current team picks the given player from the pool. Returns `false`, doing nothing, if
+    /// the player isn't in the pool or the draft is finished.
+    pub fn pick(&mut self, id: Ulid) -> bool {
+        let (Some(team), Some(idx)) = (
+            self.current_team(),
+            self.pool.iter().position(|p| p.id == id),
+        ) else {
+            return false;
+        };
+
+        let player = self.pool.remove(idx);
+        self.teams[team].push(player.clone());
+        self.picks.push(Pick {
+            round: self.round(),
+            team,
+            player,
+        });
+        true
+    }
+
+    /// The current team picks the best available player by skill.
+    pub fn auto_pick(&mut self) -> bool {
+        let best = self
+            .pool
+            .iter()
+            .enumerate()
+            .max_by_key(|&(idx, p)| (p.skill, std::cmp::Reverse(idx)))
+            .map(|(_, p)| p.id);
+        best.is_some_and(|id| self.pick(id))
+    }
+
+    /// Lets every remaining pick be made by [`Draft::auto_pick`].
+    pub fn simulate(&mut self) {
+        while self.auto_pick() {}
+    }
+
+    /// Takes back the last pick, returning the player to the pool.
+    pub fn undo(&mut self) -> Option<Pick> {
+        let pick = self.picks.pop()?;
+        self.teams[pick.team].pop();
+        self.pool.push(pick.player.clone());
+        Some(pick)
+    }
+}
+
+/// Every pick on its own line, skills left out when `hide_skills` is set.
+pub fn pick_log(picks: &[Pick], hide_skills: bool) -> String {
+    picks
+        .iter()
+        .enumerate()
+        .map(|(idx, pick)| {
+            format!(
+                "{}. Round {}: Team {} picks {}\n",
+                idx + 1,
+                pick.round + 1,
+                pick.team + 1,
+                pick.player.pretty_name(hide_skills)
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::Seed;
+
+    fn players(names: &[(&str, u32)]) -> Vec<Player> {
+        names
+            .iter()
+            .map(|&(name, skill)| Player {
+                name: name.to_owned(),
+                skill,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn draft(order: DraftOrder) -> Draft {
+        let captains = players(&[("A", 0), ("B", 0)]);
+        let pool = players(&[("p1", 50), ("p2", 40), ("p3", 30), ("p4", 20), ("p5", 10)]);
+        Draft::new(captains, pool, order, &mut Seed(1).rng())
+    }
+
+    fn picking_teams(mut draft: Draft) -> Vec<usize> {
+        let mut teams = Vec::new();
+        while let Some(team) = draft.current_team() {
+            teams.push(team);
+            draft.auto_pick();
+        }
+        teams
+    }
+
+    #[test]
+    fn snake_reverses_every_other_round() {
+        assert_eq!(picking_teams(draft(DraftOrder::Snake)), [0, 1, 1, 0, 0]);
+        assert_eq!(picking_teams(draft(DraftOrder::Linear)), [0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn random_rounds_give_every_team_one_pick() {
+        let teams = picking_teams(draft(DraftOrder::Random));
+        for round in teams.chunks(2).filter(|round| round.len() == 2) {
+            assert_ne!(round[0], round[1]);
+        }
+    }
+
+    #[test]
+    fn simulate_picks_the_best_available() {
+        let mut draft = draft(DraftOrder::Snake);
+        draft.simulate();
+        assert!(draft.is_finished());
+        let names = |team: &[Player]| team.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&draft.teams()[0]), ["A", "p1", "p4", "p5"]);
+        assert_eq!(names(&draft.teams()[1]), ["B", "p2", "p3"]);
+        assert_eq!(draft.picks()[2].round, 1);
+    }
+
+    #[test]
+    fn undo_returns_the_player_and_the_turn() {
+        let mut draft = draft(DraftOrder::Snake);
+        let id = draft.pool()[2].id;
+        assert!(draft.pick(id));
+        assert!(!draft.pick(id));
+        assert_eq!(draft.current_team(), Some(1));
+
+        let undone = draft.undo().unwrap();
+        assert_eq!(undone.player.id, id);
+        assert_eq!(undone.team, 0);
+        assert_eq!(draft.current_team(), Some(0));
+        assert_eq!(draft.pool().len(), 5);
+        assert_eq!(draft.teams()[0].len(), 1);
+        assert!(draft.picks().is_empty());
+        assert!(draft.undo().is_none());
+    }
+
+    #[test]
+    fn pick_log_lists_every_pick() {
+        let mut draft = draft(DraftOrder::Linear);
+        draft.auto_pick();
+        draft.auto_pick();
+        assert_eq!(
+            pick_log(draft.picks(), false),
+            "1. Round 1: Team 1 picks p1 (50)\n2. Round 1: Team 2 picks p2 (40)\n"
+        );
+    }
+}
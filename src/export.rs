@@ -0,0 +1,657 @@
+//! Writing teams and rosters out for sharing.
+
+use crate::balancer::{Attribute, Player, Role, sum_attribute, sum_skill};
+use std::collections::BTreeMap;
+
+/// Template the Results tab starts with, see [`template`].
+pub const DEFAULT_TEMPLATE: &str = "{team} ({skill}): {players}";
+
+/// A way of writing out teams.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ExportFormat {
+    #[default]
+    Text,
+    /// Bold headers and captains, for Discord.
+    Markdown,
+    /// Aligned columns in a Discord code block.
+    CodeBlock,
+    Json,
+    Csv,
+    /// The user's own layout, see [`template`].
+    Template,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 6] = [
+        ExportFormat::Text,
+        ExportFormat::Markdown,
+        ExportFormat::CodeBlock,
+        ExportFormat::Json,
+        ExportFormat::Csv,
+        ExportFormat::Template,
+    ];
+
+    /// Short name, for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "Plain text",
+            ExportFormat::Markdown => "Discord Markdown",
+            ExportFormat::CodeBlock => "Discord code block",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Template => "Template",
+        }
+    }
+
+    /// Name on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "text",
+            ExportFormat::Markdown => "markdown",
+            ExportFormat::CodeBlock => "code-block",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Template => "template",
+        }
+    }
+
+    /// File extension of files in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Text | ExportFormat::CodeBlock | ExportFormat::Template => "txt",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Settings shared by the export formats.
+#[derive(Clone, Copy, Debug)]
+pub struct ExportOptions<'a> {
+    /// Attributes totalled per team in JSON.
+    pub attributes: &'a [Attribute],
+    /// Roles, named on each player in JSON.
+    pub roles: &'a [Role],
+    /// Leave skills out of the formats meant to be read by people.
+    pub hide_skills: bool,
+    /// Layout for [`ExportFormat::Template`].
+    pub template: &'a str,
+}
+
+/// Teams in the given format.
+pub fn export(
+    format: ExportFormat,
+    teams: &[Vec<Player>],
+    bench: &[Player],
+    options: &ExportOptions,
+) -> String {
+    match format {
+        ExportFormat::Text => text(teams, bench, options.hide_skills),
+        ExportFormat::Markdown => markdown(teams, bench, options.hide_skills),
+        ExportFormat::CodeBlock => code_block(teams, bench, options.hide_skills),
+        ExportFormat::Json => json(teams, bench, options.attributes, options.roles),
+        ExportFormat::Csv => teams_csv(teams, bench),
+        ExportFormat::Template => template(options.template, teams, bench, options.hide_skills),
+    }
+}
+
+/// Teams as plain text, the way the Results tab copies them. Skills are left out when
+/// `hide_skills` is set.
+pub fn text(teams: &[Vec<Player>], bench: &[Player], hide_skills: bool) -> String {
+    let mut output = String::new();
+
+    for (team_idx, team) in teams.iter().enumerate() {
+        let team_header = if hide_skills {
+            format!("Team {}:\n", team_idx + 1)
+        } else {
+            format!("Team {} ({}):\n", team_idx + 1, sum_skill(team))
+        };
+        output.push_str(&team_header);
+
+        for player in team {
+            output.push_str(&format!("- {}\n", player.pretty_name(hide_skills)));
+        }
+
+        output.push('\n');
+    }
+
+    if !bench.is_empty() {
+        output.push_str("Bench:\n");
+        for player in bench {
+            output.push_str(&format!("- {}\n", player.pretty_name(hide_skills)));
+        }
+    }
+
+    output
+}
+
+/// Teams as Markdown, with a bold header per team and captains in bold. Renders well in Discord.
+/// Characters Markdown gives a meaning to are escaped in names.
+pub fn markdown(teams: &[Vec<Player>], bench: &[Player], hide_skills: bool) -> String {
+    let line = |player: &Player| {
+        let name = escape_markdown(&player.pretty_name(true));
+        let skill = if hide_skills {
+            String::new()
+        } else {
+            format!(" ({})", player.skill)
+        };
+        if player.is_captain {
+            format!("- **{}**{}\n", name, skill)
+        } else {
+            format!("- {}{}\n", name, skill)
+        }
+    };
+
+    let mut output = String::new();
+
+    for (team_idx, team) in teams.iter().enumerate() {
+        if hide_skills {
+            output.push_str(&format!("**Team {}**\n", team_idx + 1));
+        } else {
+            output.push_str(&format!(
+                "**Team {}** ({})\n",
+                team_idx + 1,
+                sum_skill(team)
+            ));
+        }
+
+        for player in team {
+            output.push_str(&line(player));
+        }
+
+        output.push('\n');
+    }
+
+    if !bench.is_empty() {
+        output.push_str("**Bench**\n");
+        for player in bench {
+            output.push_str(&line(player));
+        }
+    }
+
+    output
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '|' | '~') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Teams side by side in a Markdown code block, so Discord shows them in aligned columns.
+/// Captains are marked with an asterisk, as emoji would throw the columns off. Backticks in names
+/// are swapped for a look-alike, as they could end the block.
+pub fn code_block(teams: &[Vec<Player>], bench: &[Player], hide_skills: bool) -> String {
+    let cell = |player: &Player| {
+        let name = player.pretty_name(hide_skills).replace('`', "ˋ");
+        match name.strip_prefix("⭐ ") {
+            Some(name) => format!("* {}", name),
+            None => name,
+        }
+    };
+
+    let mut columns: Vec<Vec<String>> = teams
+        .iter()
+        .enumerate()
+        .map(|(team_idx, team)| {
+            let header = if hide_skills {
+                format!("Team {}", team_idx + 1)
+            } else {
+                format!("Team {} ({})", team_idx + 1, sum_skill(team))
+            };
+            let underline = "-".repeat(header.chars().count());
+            [header, underline]
+                .into_iter()
+                .chain(team.iter().map(cell))
+                .collect()
+        })
+        .collect();
+    if !bench.is_empty() {
+        columns.push(
+            ["Bench".to_owned(), "-----".to_owned()]
+                .into_iter()
+                .chain(bench.iter().map(cell))
+                .collect(),
+        );
+    }
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|column| {
+            column
+                .iter()
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut output = String::from("```\n");
+    for row in 0..rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, &width)| {
+                let cell = column.get(row).map_or("", String::as_str);
+                format!("{:width$}", cell, width = width)
+            })
+            .collect();
+        output.push_str(cells.join("   ").trim_end());
+        output.push('\n');
+    }
+    output.push_str("```\n");
+    output
+}
+
+#[derive(serde::Serialize)]
+struct JsonTeams<'a> {
+    teams: Vec<JsonTeam<'a>>,
+    bench: Vec<JsonPlayer<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonTeam<'a> {
+    name: String,
+    skill: u32,
+    size: usize,
+    /// Total of each attribute, by name.
+    attributes: BTreeMap<&'a str, u32>,
+    players: Vec<JsonPlayer<'a>>,
+}
+
+/// A player as exported, with attributes and roles by name. Notes, tags and when they were last
+/// benched are for the roster's owner only.
+#[derive(serde::Serialize)]
+struct JsonPlayer<'a> {
+    name: &'a str,
+    skill: u32,
+    is_captain: bool,
+    attributes: BTreeMap<&'a str, u32>,
+    roles: Vec<&'a str>,
+}
+
+impl<'a> JsonPlayer<'a> {
+    fn new(player: &'a Player, attributes: &'a [Attribute], roles: &'a [Role]) -> Self {
+        JsonPlayer {
+            name: &player.name,
+            skill: player.skill,
+            is_captain: player.is_captain,
+            attributes: attributes
+                .iter()
+                .filter_map(|a| Some((a.name.as_str(), *player.attributes.get(&a.id)?)))
+                .collect(),
+            roles: roles
+                .iter()
+                .filter(|role| player.roles.contains(&role.id))
+                .map(|role| role.name.as_str())
+                .collect(),
+        }
+    }
+}
+
+/// Teams as pretty-printed JSON, an object with a `teams` array, each with its `name`, total
+/// `skill`, `size`, `attributes` totals and `players`, and the `bench`. Players have a `name`,
+/// `skill`, `is_captain`, and their `attributes` and `roles` by name.
+pub fn json<'a>(
+    teams: &'a [Vec<Player>],
+    bench: &'a [Player],
+    attributes: &'a [Attribute],
+    roles: &'a [Role],
+) -> String {
+    let players = |players: &'a [Player]| -> Vec<JsonPlayer<'a>> {
+        players
+            .iter()
+            .map(|player| JsonPlayer::new(player, attributes, roles))
+            .collect()
+    };
+    let output = JsonTeams {
+        teams: teams
+            .iter()
+            .enumerate()
+            .map(|(team_idx, team)| JsonTeam {
+                name: format!("Team {}", team_idx + 1),
+                skill: sum_skill(team),
+                size: team.len(),
+                attributes: attributes
+                    .iter()
+                    .map(|a| (a.name.as_str(), sum_attribute(team, a.id)))
+                    .collect(),
+                players: players(team),
+            })
+            .collect(),
+        bench: players(bench),
+    };
+
+    serde_json::to_string_pretty(&output).expect("Failed to serialize teams to JSON")
+}
+
+/// Placeholders of [`template`], with what they stand for.
+pub const TEMPLATE_PLACEHOLDERS: [(&str, &str); 7] = [
+    ("{team}", "name of the team, like \"Team 1\""),
+    ("{number}", "number of the team"),
+    (
+        "{skill}",
+        "total skill of the team, empty when skills are hidden",
+    ),
+    ("{size}", "number of players"),
+    ("{captain}", "name of the captain"),
+    ("{players}", "names separated by commas"),
+    ("{list}", "names on their own lines, with a dash"),
+];
+
+/// Teams written with the user's own layout. `template` is filled in for every team, see
+/// [`TEMPLATE_PLACEHOLDERS`], and the teams are separated by a blank line. Benched players are
+/// listed at the end.
+pub fn template(
+    template: &str,
+    teams: &[Vec<Player>],
+    bench: &[Player],
+    hide_skills: bool,
+) -> String {
+    let name = |player: &Player| {
+        if player.name.is_empty() {
+            "<unnamed>".to_owned()
+        } else {
+            player.name.clone()
+        }
+    };
+    let names = |players: &[Player]| players.iter().map(name).collect::<Vec<_>>();
+
+    let mut blocks: Vec<String> = teams
+        .iter()
+        .enumerate()
+        .map(|(team_idx, team)| {
+            let captain = team.iter().find(|p| p.is_captain).map(name);
+            fill(template, |placeholder| {
+                Some(match placeholder {
+                    "{team}" => format!("Team {}", team_idx + 1),
+                    "{number}" => (team_idx + 1).to_string(),
+                    "{skill}" if hide_skills => String::new(),
+                    "{skill}" => sum_skill(team).to_string(),
+                    "{size}" => team.len().to_string(),
+                    "{captain}" => captain.clone().unwrap_or_default(),
+                    "{players}" => names(team).join(", "),
+                    "{list}" => {
+                        let list: Vec<String> =
+                            names(team).iter().map(|n| format!("- {}", n)).collect();
+                        list.join("\n")
+                    }
+                    _ => return None,
+                })
+            })
+        })
+        .collect();
+
+    if !bench.is_empty() {
+        blocks.push(format!("Bench: {}", names(bench).join(", ")));
+    }
+
+    let mut output = blocks.join("\n\n");
+    output.push('\n');
+    output
+}
+
+/// `template` with every placeholder replaced by its `value`, or kept when it has none. Done in
+/// one pass, so a name like "{list}" is not taken for a placeholder.
+fn fill(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let filled = rest[start..]
+            .find('}')
+            .map(|end| &rest[start..=start + end])
+            .and_then(|placeholder| Some((placeholder.len(), value(placeholder)?)));
+        match filled {
+            Some((len, value)) => {
+                output.push_str(&value);
+                rest = &rest[start + len..];
+            }
+            None => {
+                output.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// The roster as CSV, one player per row with their skill, captaincy, attributes, tags and notes.
+/// It reads back with the default column mapping of [`crate::import`].
+pub fn roster_csv(players: &[Player], attributes: &[Attribute]) -> String {
+    let mut header = vec!["Name".to_owned(), "Skill".to_owned(), "Captain".to_owned()];
+    header.extend(attributes.iter().map(|a| a.name.clone()));
+    header.extend(["Tags".to_owned(), "Notes".to_owned()]);
+
+    let rows = players.iter().map(|player| {
+        let mut row = vec![
+            player.name.clone(),
+            player.skill.to_string(),
+            yes_no(player.is_captain),
+        ];
+        row.extend(attributes.iter().map(|a| {
+            player
+                .attributes
+                .get(&a.id)
+                .copied()
+                .unwrap_or(0)
+                .to_string()
+        }));
+        row.extend([player.tags.join(", "), player.notes.clone()]);
+        row
+    });
+
+    write_csv(header, rows)
+}
+
+/// Teams as CSV, one player per row with the team they are on. Benched players are on "Bench".
+pub fn teams_csv(teams: &[Vec<Player>], bench: &[Player]) -> String {
+    let header = ["Team", "Name", "Skill", "Captain"]
+        .map(str::to_owned)
+        .to_vec();
+
+    let named_teams = teams
+        .iter()
+        .enumerate()
+        .map(|(team_idx, team)| (format!("Team {}", team_idx + 1), team.as_slice()))
+        .chain(std::iter::once(("Bench".to_owned(), bench)));
+    let rows = named_teams.flat_map(|(team_name, team)| {
+        team.iter().map(move |player| {
+            vec![
+                team_name.clone(),
+                player.name.clone(),
+                player.skill.to_string(),
+                yes_no(player.is_captain),
+            ]
+        })
+    });
+
+    write_csv(header, rows)
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_owned()
+}
+
+fn write_csv(header: Vec<String>, rows: impl Iterator<Item = Vec<String>>) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in std::iter::once(header).chain(rows) {
+        writer.write_record(&row).expect("Failed to write CSV");
+    }
+    let bytes = writer.into_inner().expect("Failed to write CSV");
+    String::from_utf8(bytes).expect("CSV of UTF-8 strings is UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{Table, guess_columns, read_players};
+
+    fn player(name: &str, skill: u32, is_captain: bool) -> Player {
+        Player {
+            name: name.to_owned(),
+            skill,
+            is_captain,
+            ..Default::default()
+        }
+    }
+
+    fn teams() -> Vec<Vec<Player>> {
+        vec![
+            vec![player("Ann", 1200, true), player("Bob", 800, false)],
+            vec![player("Cat", 1000, false)],
+        ]
+    }
+
+    #[test]
+    fn text_leaves_hidden_skills_out() {
+        let bench = [player("Dan", 500, false)];
+        assert_eq!(
+            text(&teams(), &bench, false),
+            "Team 1 (2000):\n- ⭐ Ann (1200)\n- Bob (800)\n\nTeam 2 (1000):\n- Cat (1000)\n\nBench:\n- Dan (500)\n"
+        );
+        assert_eq!(
+            text(&teams(), &[], true),
+            "Team 1:\n- ⭐ Ann\n- Bob\n\nTeam 2:\n- Cat\n\n"
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_names() {
+        let teams = vec![vec![
+            player("*star*", 10, true),
+            player("snake_case|`x`", 5, false),
+        ]];
+        assert_eq!(
+            markdown(&teams, &[], false),
+            "**Team 1** (15)\n- **⭐ \\*star\\*** (10)\n- snake\\_case\\|\\`x\\` (5)\n\n"
+        );
+    }
+
+    #[test]
+    fn code_block_aligns_columns() {
+        let mut teams = teams();
+        teams[1][0].name = "`Cat`".to_owned();
+        assert_eq!(
+            code_block(&teams, &[], true),
+            "```\nTeam 1   Team 2\n------   ------\n* Ann    ˋCatˋ\nBob\n```\n"
+        );
+    }
+
+    #[test]
+    fn template_fills_every_placeholder_once() {
+        let mut teams = teams();
+        teams[1][0].name = "{list}".to_owned();
+        let output = template(
+            "{team}/{number} ({skill}, {size}) {captain}: {players} {unknown}\n{list}",
+            &teams,
+            &[player("Dan", 0, false)],
+            false,
+        );
+        assert_eq!(
+            output,
+            "Team 1/1 (2000, 2) Ann: Ann, Bob {unknown}\n- Ann\n- Bob\n\n\
+             Team 2/2 (1000, 1) : {list} {unknown}\n- {list}\n\n\
+             Bench: Dan\n"
+        );
+    }
+
+    #[test]
+    fn template_hides_skills() {
+        assert_eq!(
+            template(DEFAULT_TEMPLATE, &teams(), &[], true),
+            "Team 1 (): Ann, Bob\n\nTeam 2 (): Cat\n"
+        );
+        assert_eq!(template("{", &teams()[..1], &[], false), "{\n");
+    }
+
+    #[test]
+    fn roster_csv_reads_back() {
+        let speed = Attribute::new("Speed".to_owned());
+        let mut players = vec![
+            player("Ann, the first", 1200, true),
+            player("Bob", 800, false),
+        ];
+        players[0].attributes.insert(speed.id, 4);
+        players[0].tags = vec!["left".to_owned(), "fast".to_owned()];
+        players[1].notes = "Says \"hi\"".to_owned();
+
+        let csv = roster_csv(&players, std::slice::from_ref(&speed));
+        let table = Table::from_csv(&csv).unwrap();
+        let (columns, has_header) = guess_columns(&table, std::slice::from_ref(&speed));
+        let (imported, errors) = read_players(&table, &columns, has_header);
+        assert!(errors.is_empty());
+
+        let read: Vec<Player> = imported.into_iter().map(|p| p.into_player()).collect();
+        for (read, written) in read.iter().zip(&players) {
+            assert_eq!(read.name, written.name);
+            assert_eq!(read.skill, written.skill);
+            assert_eq!(read.is_captain, written.is_captain);
+            assert_eq!(read.tags, written.tags);
+            assert_eq!(read.notes, written.notes);
+        }
+        assert_eq!(read[0].attributes[&speed.id], 4);
+    }
+
+    #[test]
+    fn teams_csv_and_json_list_everyone() {
+        let bench = [player("Dan", 500, false)];
+        assert_eq!(
+            teams_csv(&teams(), &bench),
+            "Team,Name,Skill,Captain\nTeam 1,Ann,1200,yes\nTeam 1,Bob,800,no\n\
+             Team 2,Cat,1000,no\nBench,Dan,500,no\n"
+        );
+
+        let value: serde_json::Value =
+            serde_json::from_str(&json(&teams(), &bench, &[], &[])).unwrap();
+        assert_eq!(value["teams"][0]["name"], "Team 1");
+        assert_eq!(value["teams"][0]["skill"], 2000);
+        assert_eq!(value["teams"][1]["size"], 1);
+        assert_eq!(value["bench"][0]["name"], "Dan");
+    }
+
+    #[test]
+    fn json_names_attributes_and_roles_without_private_fields() {
+        let speed = Attribute::new("Speed".to_owned());
+        let keeper = Role::new("Keeper".to_owned());
+        let mut teams = teams();
+        teams[0][0].attributes.insert(speed.id, 4);
+        teams[0][0].roles.insert(keeper.id);
+        teams[0][0].notes = "owes a fiver".to_owned();
+        teams[0][0].tags = vec!["left".to_owned()];
+        teams[0][0].last_benched = Some(3);
+        let mut bench = [player("Dan", 500, false)];
+        bench[0].roles.insert(keeper.id);
+
+        let output = json(
+            &teams,
+            &bench,
+            std::slice::from_ref(&speed),
+            std::slice::from_ref(&keeper),
+        );
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let ann = &value["teams"][0]["players"][0];
+        assert_eq!(
+            *ann,
+            serde_json::json!({
+                "name": "Ann",
+                "skill": 1200,
+                "is_captain": true,
+                "attributes": { "Speed": 4 },
+                "roles": ["Keeper"],
+            })
+        );
+        assert_eq!(value["teams"][0]["attributes"]["Speed"], 4);
+        assert_eq!(value["bench"][0]["roles"][0], "Keeper");
+        assert!(!output.contains("owes a fiver"));
+        assert!(!output.contains(&keeper.id.to_string()));
+    }
+}
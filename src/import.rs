@@ -0,0 +1,604 @@
+//! Reading players from spreadsheets and pasted text.
+
+use crate::balancer::{Attribute, MAX_SKILL, Player};
+use std::collections::BTreeMap;
+use ulid::Ulid;
+
+/// What a column of an imported table holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Ignore,
+    Name,
+    Skill,
+    Captain,
+    /// Values of the roster's attribute with this id.
+    Attribute(Ulid),
+    /// Tags separated by commas or semicolons.
+    Tags,
+    Notes,
+}
+
+impl Column {
+    /// Short name, for display. Attributes are named by the caller.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Column::Ignore => "Ignore",
+            Column::Name => "Name",
+            Column::Skill => "Skill",
+            Column::Captain => "Captain",
+            Column::Attribute(_) => "Attribute",
+            Column::Tags => "Tags",
+            Column::Notes => "Notes",
+        }
+    }
+}
+
+/// Cells of a CSV text, as read before any column is interpreted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Reads CSV text. Tabs or semicolons are used instead of commas when the first line has
+    /// more of them, so rows copied from a spreadsheet can be pasted as they are.
+    pub fn from_csv(text: &str) -> Result<Table, csv::Error> {
+        let first_line = text.lines().next().unwrap_or("");
+        let delimiter = [b',', b'\t', b';']
+            .into_iter()
+            .max_by_key(|&d| first_line.bytes().filter(|&b| b == d).count())
+            .filter(|&d| first_line.contains(d as char))
+            .unwrap_or(b',');
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            rows.push(record.iter().map(str::to_owned).collect());
+        }
+        Ok(Table { rows })
+    }
+
+    /// Number of columns of the widest row.
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+}
+
+/// Guesses what each column holds from the header row, or from the cells of the first row when
+/// there is no header. Also returns whether the first row looks like a header.
+pub fn guess_columns(table: &Table, attributes: &[Attribute]) -> (Vec<Column>, bool) {
+    let Some(first) = table.rows.first() else {
+        return (Vec::new(), false);
+    };
+
+    let from_header: Vec<Column> = (0..table.width())
+        .map(|idx| {
+            let header = first.get(idx).map_or("", String::as_str).to_lowercase();
+            if let Some(attribute) = attributes.iter().find(|a| a.name.to_lowercase() == header) {
+                return Column::Attribute(attribute.id);
+            }
+            match header.as_str() {
+                "name" | "player" | "nick" | "nickname" => Column::Name,
+                "skill" | "skill level" | "rating" | "elo" | "mmr" | "rank" => Column::Skill,
+                "captain" | "is captain" | "cap" => Column::Captain,
+                "tags" | "tag" => Column::Tags,
+                "notes" | "note" | "comment" | "comments" => Column::Notes,
+                _ => Column::Ignore,
+            }
+        })
+        .collect();
+
+    if from_header.contains(&Column::Name) {
+        return (from_header, true);
+    }
+
+    // No header: the first text column is the name, the first number the skill
+    let mut columns = vec![Column::Ignore; table.width()];
+    let is_number = |cell: &str| parse_number(cell).is_some();
+    if let Some(name) = first.iter().position(|cell| !is_number(cell)) {
+        columns[name] = Column::Name;
+    }
+    if let Some(skill) = first.iter().position(|cell| is_number(cell)) {
+        columns[skill] = Column::Skill;
+    }
+    (columns, false)
+}
+
+/// A player read from an imported row. Fields without a column are `None`, so they can be
+/// merged into an existing player without overwriting what the row didn't say.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedPlayer {
+    pub name: String,
+    pub skill: Option<u32>,
+    pub is_captain: Option<bool>,
+    pub attributes: BTreeMap<Ulid, u32>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+}
+
+impl ImportedPlayer {
+    pub fn new(name: String) -> Self {
+        ImportedPlayer {
+            name,
+            ..Default::default()
+        }
+    }
+
+    /// Sets what this row says on `player`, keeping everything else.
+    pub fn apply_to(&self, player: &mut Player) {
+        player.name = self.name.clone();
+        if let Some(skill) = self.skill {
+            player.skill = skill;
+        }
+        if let Some(is_captain) = self.is_captain {
+            player.is_captain = is_captain;
+        }
+        player.attributes.extend(&self.attributes);
+        for tag in &self.tags {
+            if !player.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                player.tags.push(tag.clone());
+            }
+        }
+        if let Some(notes) = &self.notes {
+            player.notes = notes.clone();
+        }
+    }
+
+    pub fn into_player(self) -> Player {
+        let mut player = Player::default();
+        self.apply_to(&mut player);
+        player
+    }
+}
+
+/// A row that could not be read, numbered from 1 like in a spreadsheet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowError {
+    pub row: usize,
+    pub cells: Vec<String>,
+    pub message: String,
+}
+
+/// Reads every row of `table` with the given meaning of each column, skipping the first row
+/// when it is a header.
+pub fn read_players(
+    table: &Table,
+    columns: &[Column],
+    has_header: bool,
+) -> (Vec<ImportedPlayer>, Vec<RowError>) {
+    let mut players = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, cells) in table.rows.iter().enumerate().skip(usize::from(has_header)) {
+        match read_row(cells, columns) {
+            Ok(player) => players.push(player),
+            Err(message) => errors.push(RowError {
+                row: idx + 1,
+                cells: cells.clone(),
+                message,
+            }),
+        }
+    }
+
+    (players, errors)
+}
+
+fn read_row(cells: &[String], columns: &[Column]) -> Result<ImportedPlayer, String> {
+    let mut player = ImportedPlayer::default();
+
+    for (&column, cell) in columns.iter().zip(cells) {
+        match column {
+            Column::Ignore => {}
+            Column::Name => player.name = cell.clone(),
+            Column::Skill if cell.is_empty() => {}
+            Column::Skill => player.skill = Some(parse_value(cell, "skill ")?),
+            Column::Captain => {
+                player.is_captain =
+                    Some(parse_flag(cell).ok_or(format!("captain `{}` is not yes or no", cell))?);
+            }
+            Column::Attribute(_) if cell.is_empty() => {}
+            Column::Attribute(id) => {
+                player.attributes.insert(id, parse_value(cell, "")?);
+            }
+            Column::Tags => {
+                player.tags = cell
+                    .split([',', ';'])
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+            }
+            Column::Notes => player.notes = Some(cell.clone()),
+        }
+    }
+
+    if player.name.is_empty() {
+        return Err("the name is missing".to_owned());
+    }
+    Ok(player)
+}
+
+/// A skill or attribute value of a cell, named `what` in the errors.
+fn parse_value(cell: &str, what: &str) -> Result<u32, String> {
+    let value = parse_number(cell).ok_or(format!("{}`{}` is not a number", what, cell))?;
+    if value > MAX_SKILL {
+        return Err(format!("{}`{}` is over {}", what, cell, MAX_SKILL));
+    }
+    Ok(value)
+}
+
+/// A skill or attribute value. Thousands separators and decimals are accepted, negative numbers
+/// are not. A comma must be followed by three digits, as "1,5" may mean one and a half.
+pub fn parse_number(cell: &str) -> Option<u32> {
+    let cleaned: String = cell
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '\''))
+        .collect();
+    if let Ok(value) = cleaned.parse::<u32>() {
+        return Some(value);
+    }
+
+    let (integer, fraction) = cleaned.split_once('.').unwrap_or((&cleaned, ""));
+    let mut groups = integer.split(',');
+    if groups.next().is_some_and(str::is_empty)
+        || groups.any(|group| group.len() != 3)
+        || fraction.contains(',')
+    {
+        return None;
+    }
+
+    let value: f64 = cleaned.replace(',', "").parse().ok()?;
+    (value.is_finite() && value >= 0.0 && value <= u32::MAX as f64).then(|| value.round() as u32)
+}
+
+fn parse_flag(cell: &str) -> Option<bool> {
+    match cell.to_lowercase().as_str() {
+        "" | "no" | "n" | "false" | "0" | "-" => Some(false),
+        "yes" | "y" | "true" | "1" | "x" | "✔" | "captain" | "c" => Some(true),
+        _ => None,
+    }
+}
+
+/// Words marking a captain in pasted text.
+const CAPTAIN_MARKERS: [&str; 8] = ["captain", "cap", "(c)", "[c]", "c:", "⭐", "★", "👑"];
+
+/// Reads pasted text with one player per line, like `Alice 1200`, `Bob, 950, captain` or
+/// `* Carol - 14000`. List bullets and numbering are ignored, the last number on a line is the
+/// skill. A player named again is an error, the first line naming them is kept. Lines are
+/// numbered from 1 in the errors.
+pub fn parse_text(text: &str) -> (Vec<ImportedPlayer>, Vec<RowError>) {
+    let mut players: Vec<ImportedPlayer> = Vec::new();
+    // Line of each player in `players`
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let player = match parse_line(line) {
+            Ok(player) => player,
+            Err(message) => {
+                errors.push(RowError {
+                    row: idx + 1,
+                    cells: vec![line.trim().to_owned()],
+                    message,
+                });
+                continue;
+            }
+        };
+
+        match players
+            .iter()
+            .position(|p| same_name(&p.name, &player.name))
+        {
+            Some(earlier) => errors.push(RowError {
+                row: idx + 1,
+                cells: vec![line.trim().to_owned()],
+                message: format!("{} is already on line {}", player.name, rows[earlier]),
+            }),
+            None => {
+                players.push(player);
+                rows.push(idx + 1);
+            }
+        }
+    }
+
+    (players, errors)
+}
+
+/// A single line of pasted text, or why it can't be read.
+fn parse_line(line: &str) -> Result<ImportedPlayer, String> {
+    let line = line
+        .trim()
+        .trim_start_matches(['*', '-', '•', '+', '>', '–'])
+        .trim_start();
+    // Numbered lists, like "1. " or "2) ", but not skills like "2.5k"
+    let number = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let line = match line[number..].strip_prefix(['.', ')']) {
+        Some(rest) if number > 0 && rest.starts_with(char::is_whitespace) => rest,
+        _ => line,
+    };
+
+    // Separators become spaces, except commas between digits which may group thousands
+    let chars: Vec<char> = line.chars().collect();
+    let mut cleaned = String::new();
+    for (idx, &c) in chars.iter().enumerate() {
+        let between_digits = idx > 0
+            && chars[idx - 1].is_ascii_digit()
+            && chars.get(idx + 1).is_some_and(char::is_ascii_digit);
+        match c {
+            ',' if between_digits => cleaned.push(c),
+            ',' | ';' | '|' | '\t' | '–' | '—' | '=' => cleaned.push(' '),
+            ':' if !between_digits => cleaned.push(' '),
+            _ => cleaned.push(c),
+        }
+    }
+
+    let words: Vec<&str> = cleaned.split_whitespace().filter(|w| *w != "-").collect();
+    // Only numbers keep their commas
+    if let Some(word) = words
+        .iter()
+        .find(|word| word.contains(',') && parse_skill(word).is_none())
+    {
+        return Err(format!("skill `{}` is not a number", word));
+    }
+    let skill_idx = words.iter().rposition(|word| parse_skill(word).is_some());
+    let mut name_words = Vec::new();
+    let mut is_captain = None;
+    for (idx, word) in words.iter().enumerate() {
+        if Some(idx) == skill_idx {
+            continue;
+        }
+        if CAPTAIN_MARKERS.contains(&word.to_lowercase().as_str()) {
+            is_captain = Some(true);
+        } else {
+            name_words.push(word.trim_start_matches('@'));
+        }
+    }
+    let skill = skill_idx.and_then(|idx| parse_skill(words[idx]));
+    if let Some(idx) = skill_idx
+        && skill.is_some_and(|skill| skill > MAX_SKILL)
+    {
+        return Err(format!("skill `{}` is over {}", words[idx], MAX_SKILL));
+    }
+
+    let name = name_words.join(" ");
+    if name.is_empty() {
+        return Err("no name found".to_owned());
+    }
+    Ok(ImportedPlayer {
+        skill,
+        is_captain,
+        ..ImportedPlayer::new(name)
+    })
+}
+
+/// A skill in pasted text, like `1200`, `1,200` or `1.2k`.
+fn parse_skill(word: &str) -> Option<u32> {
+    let lower = word.to_lowercase();
+    match lower.strip_suffix('k') {
+        Some(thousands) => {
+            let value: f64 = thousands.parse().ok()?;
+            (value.is_finite() && value >= 0.0).then(|| (value * 1000.0).round() as u32)
+        }
+        None if lower.starts_with(|c: char| c.is_ascii_digit()) => parse_number(&lower),
+        None => None,
+    }
+}
+
+/// How many of `imported` match a player of `roster` by name, and would update them instead of
+/// being added.
+pub fn count_existing(roster: &[Player], imported: &[ImportedPlayer]) -> usize {
+    imported
+        .iter()
+        .filter(|p| find_by_name(roster, &p.name).is_some())
+        .count()
+}
+
+/// Adds `imported` to `roster`, updating players with the same name instead of adding them
+/// twice. Returns how many players were added.
+pub fn merge_players(roster: &mut Vec<Player>, imported: Vec<ImportedPlayer>) -> usize {
+    let mut added = 0;
+    for player in imported {
+        match find_by_name(roster, &player.name) {
+            Some(idx) => player.apply_to(&mut roster[idx]),
+            None => {
+                roster.push(player.into_player());
+                added += 1;
+            }
+        }
+    }
+    added
+}
+
+/// Index of the player of `roster` called `name`, ignoring case and surrounding spaces.
+pub fn find_by_name(roster: &[Player], name: &str) -> Option<usize> {
+    roster.iter().position(|p| same_name(&p.name, name))
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(players: &[ImportedPlayer]) -> Vec<&str> {
+        players.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn csv_delimiter_follows_the_first_line() {
+        let commas = Table::from_csv("Name,Skill\nAlice,1200\n\n").unwrap();
+        assert_eq!(commas.rows, [["Name", "Skill"], ["Alice", "1200"]]);
+
+        let tabs = Table::from_csv("Alice\t1,200\nBob\t950").unwrap();
+        assert_eq!(tabs.rows[0], ["Alice", "1,200"]);
+
+        let semicolons = Table::from_csv("Alice; 1,5; yes").unwrap();
+        assert_eq!(semicolons.rows[0], ["Alice", "1,5", "yes"]);
+        assert_eq!(semicolons.width(), 3);
+    }
+
+    #[test]
+    fn columns_are_guessed_from_the_header() {
+        let speed = Attribute::new("Speed".to_owned());
+        let table = Table::from_csv("Player,Rating,Speed,Cap,Age\nAlice,1200,3,yes,30").unwrap();
+        let (columns, has_header) = guess_columns(&table, std::slice::from_ref(&speed));
+        assert!(has_header);
+        assert_eq!(
+            columns,
+            [
+                Column::Name,
+                Column::Skill,
+                Column::Attribute(speed.id),
+                Column::Captain,
+                Column::Ignore
+            ]
+        );
+
+        let (players, errors) = read_players(&table, &columns, has_header);
+        assert!(errors.is_empty());
+        assert_eq!(players[0].skill, Some(1200));
+        assert_eq!(players[0].is_captain, Some(true));
+        assert_eq!(players[0].attributes[&speed.id], 3);
+    }
+
+    #[test]
+    fn columns_are_guessed_from_the_cells_without_a_header() {
+        let table = Table::from_csv("7,Alice,1200\n8,Bob,x\n9,,950").unwrap();
+        let (columns, has_header) = guess_columns(&table, &[]);
+        assert!(!has_header);
+        assert_eq!(columns, [Column::Skill, Column::Name, Column::Ignore]);
+
+        let columns = [Column::Ignore, Column::Name, Column::Skill];
+        let (players, errors) = read_players(&table, &columns, false);
+        assert_eq!(names(&players), ["Alice"]);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].row, 2);
+        assert_eq!(errors[0].message, "skill `x` is not a number");
+        assert_eq!(errors[1].message, "the name is missing");
+    }
+
+    #[test]
+    fn numbers_accept_separators_and_decimals() {
+        assert_eq!(parse_number("1,200"), Some(1200));
+        assert_eq!(parse_number("1 200"), Some(1200));
+        assert_eq!(parse_number("12.6"), Some(13));
+        assert_eq!(parse_number("1,234,567.5"), Some(1234568));
+        assert_eq!(parse_number("-5"), None);
+        assert_eq!(parse_number("1,5"), None);
+        assert_eq!(parse_number("1,2345"), None);
+        assert_eq!(parse_number(",500"), None);
+        assert_eq!(parse_number("1.500,5"), None);
+        assert_eq!(parse_number("abc"), None);
+    }
+
+    #[test]
+    fn pasted_lines_in_many_shapes() {
+        let text =
+            "Alice 1200\nBob, 950, captain\n* Carol - 14000\n2) Dan: 1,500\n\n@Erin\n3. Frank 1.2k";
+        let (players, errors) = parse_text(text);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            names(&players),
+            ["Alice", "Bob", "Carol", "Dan", "Erin", "Frank"]
+        );
+
+        let skills: Vec<Option<u32>> = players.iter().map(|p| p.skill).collect();
+        assert_eq!(
+            skills,
+            [
+                Some(1200),
+                Some(950),
+                Some(14000),
+                Some(1500),
+                None,
+                Some(1200)
+            ]
+        );
+        assert_eq!(players[1].is_captain, Some(true));
+        assert_eq!(players[0].is_captain, None);
+    }
+
+    #[test]
+    fn skills_are_not_taken_for_list_numbers() {
+        let (players, _) = parse_text("2.5k Eve\n10) 3k Finn\n1.Gus");
+        assert_eq!(names(&players), ["Eve", "Finn", "1.Gus"]);
+        assert_eq!(players[0].skill, Some(2500));
+        assert_eq!(players[1].skill, Some(3000));
+    }
+
+    #[test]
+    fn pasted_names_twice_are_errors() {
+        let (players, errors) = parse_text("alice 1300\n⭐\nAlice 1200");
+        assert_eq!(names(&players), ["alice"]);
+        assert_eq!(players[0].skill, Some(1300));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].message, "no name found");
+        assert_eq!(errors[1].row, 3);
+        assert_eq!(errors[1].message, "Alice is already on line 1");
+    }
+
+    #[test]
+    fn decimal_commas_are_errors() {
+        let table = Table::from_csv("Ann\t1,5\nBob\t1,500").unwrap();
+        let (players, errors) = read_players(&table, &[Column::Name, Column::Skill], false);
+        assert_eq!(names(&players), ["Bob"]);
+        assert_eq!(players[0].skill, Some(1500));
+        assert_eq!(errors[0].message, "skill `1,5` is not a number");
+
+        let (players, errors) = parse_text("Ann 1,5\nBob, 1,500\nCat 1,2k");
+        assert_eq!(names(&players), ["Bob"]);
+        assert_eq!(players[0].skill, Some(1500));
+        assert_eq!(errors[0].message, "skill `1,5` is not a number");
+        assert_eq!(errors[1].message, "skill `1,2k` is not a number");
+    }
+
+    #[test]
+    fn oversized_skills_are_errors() {
+        let table =
+            Table::from_csv("Name,Skill\nAnn,3000000000\nBob,35000\nCat,99999999999").unwrap();
+        let columns = [Column::Name, Column::Skill];
+        let (players, errors) = read_players(&table, &columns, true);
+        assert_eq!(names(&players), ["Bob"]);
+        assert_eq!(errors[0].message, "skill `3000000000` is over 35000");
+        assert_eq!(errors[1].row, 4);
+
+        let (players, errors) = parse_text("Ann 3000000000\nBob 35k\nCat 36k");
+        assert_eq!(names(&players), ["Bob"]);
+        assert_eq!(errors[0].message, "skill `3000000000` is over 35000");
+        assert_eq!(errors[1].message, "skill `36k` is over 35000");
+    }
+
+    #[test]
+    fn merging_updates_players_with_the_same_name() {
+        let mut roster = vec![Player {
+            name: "Alice ".to_owned(),
+            skill: 1000,
+            notes: "Keeps goal".to_owned(),
+            ..Default::default()
+        }];
+        let (players, _) = parse_text("ALICE 1200\nBob 900");
+        assert_eq!(count_existing(&roster, &players), 1);
+
+        assert_eq!(merge_players(&mut roster, players), 1);
+        assert_eq!(roster.len(), 2);
+        assert_eq!(roster[0].name, "ALICE");
+        assert_eq!(roster[0].skill, 1200);
+        assert_eq!(roster[0].notes, "Keeps goal");
+        assert_eq!(find_by_name(&roster, " bob"), Some(1));
+    }
+}
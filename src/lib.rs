@@ -0,0 +1,13 @@
+//! Team balancing and spin wheel logic behind Kallel's Utilities, usable without the GUI.
+
+pub mod balancer;
+pub mod draft;
+pub mod export;
+pub mod import;
+pub mod rating;
+pub mod render;
+pub mod save;
+pub mod seed;
+pub mod share;
+pub mod stats;
+pub mod wheel;
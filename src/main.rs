@@ -2,8 +2,11 @@
 
 mod app;
 mod cache;
+mod download;
 mod extensions;
 mod views;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
 use crate::app::App;
 
@@ -56,11 +59,14 @@ fn main() {
             .dyn_into::<web_sys::HtmlCanvasElement>()
             .expect("the_canvas_id was not a HtmlCanvasElement");
 
+        // Opened from a share link, ask before importing what it carries:
+        let shared = web::take_shared();
+
         let start_result = eframe::WebRunner::new()
             .start(
                 canvas,
                 web_options,
-                Box::new(|cc| Ok(Box::new(App::default(cc)))),
+                Box::new(|cc| Ok(Box::new(App::default(cc).with_shared(shared)))),
             )
             .await;
 
@@ -0,0 +1,584 @@
+//! Learning player ratings from recorded match results.
+
+use crate::balancer::{PastPlayer, Player};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use ulid::Ulid;
+
+/// Rating points per factor of ten in the odds of winning, for Elo.
+const ELO_SCALE: f64 = 400.0;
+/// Most rating points a team can win or lose in one Elo game.
+const ELO_K: f64 = 32.0;
+const INITIAL_DEVIATION: f64 = 350.0;
+const INITIAL_VOLATILITY: f64 = 0.06;
+/// Conversion between rating points and Glicko-2's internal scale.
+const GLICKO_SCALE: f64 = 173.7178;
+/// How much Glicko-2 volatility may change between games.
+const GLICKO_TAU: f64 = 0.5;
+/// Spread of a single performance around a player's rating, for TrueSkill.
+const TRUESKILL_BETA: f64 = INITIAL_DEVIATION / 2.0;
+/// Uncertainty added before every game, so TrueSkill ratings keep adapting.
+const TRUESKILL_TAU: f64 = INITIAL_DEVIATION / 100.0;
+/// Standard normal quantile for a 10% chance of two even teams drawing.
+const TRUESKILL_DRAW_QUANTILE: f64 = 0.125_661_346_855_074_1;
+
+/// Result of a played game.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MatchRecord {
+    /// Game number, as counted by the Team Creator.
+    pub game: u32,
+    pub teams: Vec<Vec<PastPlayer>>,
+    /// Score of each team. A plain win is recorded as one point for the winner, a draw as equal
+    /// scores.
+    pub scores: Vec<u32>,
+}
+
+impl MatchRecord {
+    pub fn new(game: u32, teams: &[Vec<Player>], scores: Vec<u32>) -> Self {
+        MatchRecord {
+            game,
+            teams: teams
+                .iter()
+                .map(|team| team.iter().map(PastPlayer::new).collect())
+                .collect(),
+            scores,
+        }
+    }
+
+    /// Teams with the highest score. All of them on a draw.
+    pub fn winners(&self) -> Vec<usize> {
+        let best = self.scores.iter().copied().max().unwrap_or(0);
+        (0..self.scores.len())
+            .filter(|&idx| self.scores[idx] == best)
+            .collect()
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.winners().len() == self.scores.len()
+    }
+
+    /// Team the given player played on.
+    pub fn team_of(&self, id: Ulid) -> Option<usize> {
+        self.teams
+            .iter()
+            .position(|team| team.iter().any(|p| p.id == id))
+    }
+
+    /// 1 if team `a` beat team `b`, 0.5 on a draw and 0 if it lost.
+    fn outcome(&self, a: usize, b: usize) -> f64 {
+        match self.scores[a].cmp(&self.scores[b]) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        }
+    }
+}
+
+/// How ratings are updated after each game. Teams are rated as a whole and every player of a
+/// team moves together.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RatingModel {
+    /// Team averages are compared, winners take points from losers.
+    #[default]
+    Elo,
+    /// Like Elo, but players with few games or erratic results move faster.
+    Glicko2,
+    /// Team strength is the sum of its players, each with their own uncertainty.
+    TrueSkill,
+}
+
+impl RatingModel {
+    pub const ALL: [RatingModel; 3] = [
+        RatingModel::Elo,
+        RatingModel::Glicko2,
+        RatingModel::TrueSkill,
+    ];
+
+    /// Short name, for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            RatingModel::Elo => "Elo",
+            RatingModel::Glicko2 => "Glicko-2",
+            RatingModel::TrueSkill => "TrueSkill",
+        }
+    }
+}
+
+/// A learned rating, on the same scale as [`Player::skill`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    pub value: f64,
+    /// Uncertainty of `value`, unused by Elo.
+    pub deviation: f64,
+    /// How erratic the player's results are, only used by Glicko-2.
+    pub volatility: f64,
+}
+
+impl Rating {
+    /// Rating of a player's first game, starting from their hand-typed skill.
+    pub fn initial(skill: u32) -> Self {
+        Rating {
+            value: skill as f64,
+            deviation: INITIAL_DEVIATION,
+            volatility: INITIAL_VOLATILITY,
+        }
+    }
+
+    /// The rating rounded to a skill value.
+    pub fn skill(&self) -> u32 {
+        self.value.round().max(0.0) as u32
+    }
+}
+
+/// Ratings of every player found in the match log.
+#[derive(Clone, Debug, Default)]
+pub struct Ratings {
+    model: RatingModel,
+    ratings: HashMap<Ulid, Rating>,
+}
+
+impl Ratings {
+    pub fn new(model: RatingModel) -> Self {
+        Ratings {
+            model,
+            ratings: HashMap::new(),
+        }
+    }
+
+    /// Replays every match of the log in order.
+    pub fn from_matches(model: RatingModel, matches: &[MatchRecord]) -> Self {
+        let mut ratings = Ratings::new(model);
+        for record in matches {
+            ratings.record(record);
+        }
+        ratings
+    }
+
+    pub fn get(&self, id: Ulid) -> Option<Rating> {
+        self.ratings.get(&id).copied()
+    }
+
+    /// Updates everyone who played in `record`. Players without a rating yet start from the
+    /// skill they had in that game. Matches with less than two teams or a score missing are
+    /// skipped.
+    pub fn record(&mut self, record: &MatchRecord) {
+        if record.teams.len() < 2 || record.scores.len() != record.teams.len() {
+            return;
+        }
+
+        let before: Vec<Vec<Rating>> = record
+            .teams
+            .iter()
+            .map(|team| {
+                team.iter()
+                    .map(|p| self.get(p.id).unwrap_or(Rating::initial(p.skill)))
+                    .collect()
+            })
+            .collect();
+
+        let after = match self.model {
+            RatingModel::Elo => elo(&before, record),
+            RatingModel::Glicko2 => glicko2(&before, record),
+            RatingModel::TrueSkill => trueskill(&before, record),
+        };
+
+        for (team, ratings) in record.teams.iter().zip(after) {
+            for (player, rating) in team.iter().zip(ratings) {
+                self.ratings.insert(player.id, rating);
+            }
+        }
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    sum / count.max(1) as f64
+}
+
+fn elo(before: &[Vec<Rating>], record: &MatchRecord) -> Vec<Vec<Rating>> {
+    let team_ratings: Vec<f64> = before
+        .iter()
+        .map(|team| mean(team.iter().map(|r| r.value)))
+        .collect();
+    let opponents = (before.len() - 1) as f64;
+
+    before
+        .iter()
+        .enumerate()
+        .map(|(team_idx, team)| {
+            let (actual, expected) = (0..before.len())
+                .filter(|&other| other != team_idx)
+                .map(|other| {
+                    let expected = 1.0
+                        / (1.0
+                            + 10f64
+                                .powf((team_ratings[other] - team_ratings[team_idx]) / ELO_SCALE));
+                    (record.outcome(team_idx, other), expected)
+                })
+                .fold((0.0, 0.0), |(a, e), (actual, expected)| {
+                    (a + actual, e + expected)
+                });
+            let delta = ELO_K * (actual - expected) / opponents;
+
+            team.iter()
+                .map(|r| Rating {
+                    value: r.value + delta,
+                    ..*r
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Every player is rated against each opposing team as if it were a single player with the
+/// team's average rating.
+fn glicko2(before: &[Vec<Rating>], record: &MatchRecord) -> Vec<Vec<Rating>> {
+    let composites: Vec<(f64, f64)> = before
+        .iter()
+        .map(|team| {
+            let mu = mean(team.iter().map(|r| r.value)) / GLICKO_SCALE;
+            let phi = mean(team.iter().map(|r| r.deviation.powi(2))).sqrt() / GLICKO_SCALE;
+            (mu, phi)
+        })
+        .collect();
+
+    let g = |phi: f64| 1.0 / (1.0 + 3.0 * phi.powi(2) / PI.powi(2)).sqrt();
+
+    before
+        .iter()
+        .enumerate()
+        .map(|(team_idx, team)| {
+            team.iter()
+                .map(|rating| {
+                    let mu = rating.value / GLICKO_SCALE;
+                    let phi = rating.deviation / GLICKO_SCALE;
+
+                    let mut inverse_v = 0.0;
+                    let mut improvement = 0.0;
+                    for (other, &(mu_j, phi_j)) in composites.iter().enumerate() {
+                        if other == team_idx {
+                            continue;
+                        }
+                        let g_j = g(phi_j);
+                        let expected = 1.0 / (1.0 + (-g_j * (mu - mu_j)).exp());
+                        inverse_v += g_j.powi(2) * expected * (1.0 - expected);
+                        improvement += g_j * (record.outcome(team_idx, other) - expected);
+                    }
+
+                    // Without an opponent whose result could have gone either way there is
+                    // nothing to learn, only the uncertainty grows
+                    if inverse_v <= f64::EPSILON {
+                        return Rating {
+                            deviation: (phi.powi(2) + rating.volatility.powi(2)).sqrt()
+                                * GLICKO_SCALE,
+                            ..*rating
+                        };
+                    }
+
+                    let v = 1.0 / inverse_v;
+                    let delta = v * improvement;
+                    let sigma = glicko2_volatility(delta, phi, v, rating.volatility);
+
+                    let phi_star = (phi.powi(2) + sigma.powi(2)).sqrt();
+                    let new_phi = 1.0 / (1.0 / phi_star.powi(2) + 1.0 / v).sqrt();
+                    let new_mu = mu + new_phi.powi(2) * improvement;
+
+                    Rating {
+                        value: new_mu * GLICKO_SCALE,
+                        deviation: new_phi * GLICKO_SCALE,
+                        volatility: sigma,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The new volatility, found with the Illinois algorithm as in Glickman's paper.
+fn glicko2_volatility(delta: f64, phi: f64, v: f64, sigma: f64) -> f64 {
+    let a = sigma.powi(2).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta.powi(2) - phi.powi(2) - v - ex) / (2.0 * (phi.powi(2) + v + ex).powi(2))
+            - (x - a) / GLICKO_TAU.powi(2)
+    };
+
+    let mut lower = a;
+    let mut upper = if delta.powi(2) > phi.powi(2) + v {
+        (delta.powi(2) - phi.powi(2) - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * GLICKO_TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * GLICKO_TAU
+    };
+
+    let mut f_lower = f(lower);
+    let mut f_upper = f(upper);
+    for _ in 0..100 {
+        if (upper - lower).abs() <= 1e-6 {
+            break;
+        }
+        let c = lower + (lower - upper) * f_lower / (f_upper - f_lower);
+        let f_c = f(c);
+        if f_c * f_upper <= 0.0 {
+            lower = upper;
+            f_lower = f_upper;
+        } else {
+            f_lower /= 2.0;
+        }
+        upper = c;
+        f_upper = f_c;
+    }
+
+    (lower / 2.0).exp()
+}
+
+/// Two-team TrueSkill updates between every pair of teams, averaged over the opponents.
+fn trueskill(before: &[Vec<Rating>], record: &MatchRecord) -> Vec<Vec<Rating>> {
+    let variances: Vec<Vec<f64>> = before
+        .iter()
+        .map(|team| {
+            team.iter()
+                .map(|r| r.deviation.powi(2) + TRUESKILL_TAU.powi(2))
+                .collect()
+        })
+        .collect();
+    let mut mean_shift: Vec<Vec<f64>> = before.iter().map(|t| vec![0.0; t.len()]).collect();
+    let mut variance_factor = mean_shift.clone();
+
+    for a in 0..before.len() {
+        for b in a + 1..before.len() {
+            let players = (before[a].len() + before[b].len()) as f64;
+            let team_mu = |t: usize| before[t].iter().map(|r| r.value).sum::<f64>();
+            let team_variance = |t: usize| variances[t].iter().sum::<f64>();
+
+            let c = (players * TRUESKILL_BETA.powi(2) + team_variance(a) + team_variance(b)).sqrt();
+            let t = (team_mu(a) - team_mu(b)) / c;
+            let epsilon = TRUESKILL_DRAW_QUANTILE * players.sqrt() * TRUESKILL_BETA / c;
+
+            // `v` moves team `a` up by `sign`, team `b` down by as much
+            let (v, w, sign) = match record.scores[a].cmp(&record.scores[b]) {
+                Ordering::Greater => {
+                    let (v, w) = truncated_win(t, epsilon);
+                    (v, w, 1.0)
+                }
+                Ordering::Less => {
+                    let (v, w) = truncated_win(-t, epsilon);
+                    (v, w, -1.0)
+                }
+                Ordering::Equal => {
+                    let (v, w) = truncated_draw(t, epsilon);
+                    (v, w, 1.0)
+                }
+            };
+
+            for (team, direction) in [(a, sign), (b, -sign)] {
+                for (idx, variance) in variances[team].iter().enumerate() {
+                    mean_shift[team][idx] += direction * variance / c * v;
+                    variance_factor[team][idx] += variance / c.powi(2) * w;
+                }
+            }
+        }
+    }
+
+    let opponents = (before.len() - 1) as f64;
+    before
+        .iter()
+        .enumerate()
+        .map(|(team_idx, team)| {
+            team.iter()
+                .enumerate()
+                .map(|(idx, r)| {
+                    let variance = variances[team_idx][idx];
+                    let factor = (1.0 - variance_factor[team_idx][idx] / opponents).max(1e-4);
+                    Rating {
+                        value: r.value + mean_shift[team_idx][idx] / opponents,
+                        deviation: (variance * factor).sqrt(),
+                        ..*r
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn truncated_win(t: f64, epsilon: f64) -> (f64, f64) {
+    let x = t - epsilon;
+    let denominator = normal_cdf(x);
+    let v = if denominator < 1e-12 {
+        -x
+    } else {
+        normal_pdf(x) / denominator
+    };
+    (v, v * (v + x))
+}
+
+fn truncated_draw(t: f64, epsilon: f64) -> (f64, f64) {
+    let denominator = normal_cdf(epsilon - t) - normal_cdf(-epsilon - t);
+    if denominator < 1e-12 {
+        return (if t < 0.0 { -t - epsilon } else { -t + epsilon }, 1.0);
+    }
+
+    let v = (normal_pdf(-epsilon - t) - normal_pdf(epsilon - t)) / denominator;
+    let w = v.powi(2)
+        + ((epsilon - t) * normal_pdf(epsilon - t) + (epsilon + t) * normal_pdf(epsilon + t))
+            / denominator;
+    (v, w)
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function, accurate to about 1e-7.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let ans = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 { ans } else { 2.0 - ans }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(value: f64, deviation: f64) -> Rating {
+        Rating {
+            value,
+            deviation,
+            volatility: INITIAL_VOLATILITY,
+        }
+    }
+
+    fn record(scores: Vec<u32>) -> MatchRecord {
+        MatchRecord {
+            game: 1,
+            teams: vec![Vec::new(); scores.len()],
+            scores,
+        }
+    }
+
+    fn player(skill: u32) -> Player {
+        Player {
+            skill,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn winners_and_draws() {
+        assert_eq!(record(vec![2, 5, 5]).winners(), [1, 2]);
+        assert!(!record(vec![2, 5, 5]).is_draw());
+        assert!(record(vec![3, 3]).is_draw());
+    }
+
+    #[test]
+    fn elo_moves_both_teams_by_the_same_amount() {
+        let before = vec![vec![rating(1000.0, 0.0)], vec![rating(1000.0, 0.0)]];
+        let after = elo(&before, &record(vec![1, 0]));
+        assert_eq!(after[0][0].value, 1000.0 + ELO_K / 2.0);
+        assert_eq!(after[1][0].value, 1000.0 - ELO_K / 2.0);
+
+        let after = elo(&before, &record(vec![1, 1]));
+        assert_eq!(after[0][0].value, 1000.0);
+    }
+
+    /// The example worked through in Glickman's "Example of the Glicko-2 system".
+    #[test]
+    fn glicko2_matches_the_paper() {
+        let before = vec![
+            vec![rating(1500.0, 200.0)],
+            vec![rating(1400.0, 30.0)],
+            vec![rating(1550.0, 100.0)],
+            vec![rating(1700.0, 300.0)],
+        ];
+        // Beats the first opponent, loses to the other two
+        let after = glicko2(&before, &record(vec![1, 0, 2, 2]));
+        let player = after[0][0];
+        assert!((player.value - 1464.06).abs() < 0.01, "{}", player.value);
+        assert!(
+            (player.deviation - 151.52).abs() < 0.01,
+            "{}",
+            player.deviation
+        );
+        assert!(
+            (player.volatility - 0.05999).abs() < 1e-5,
+            "{}",
+            player.volatility
+        );
+    }
+
+    #[test]
+    fn glicko2_learns_nothing_from_a_certain_result() {
+        let lonely = glicko2(&[vec![rating(1500.0, 200.0)]], &record(vec![1]));
+        let mismatch = glicko2(
+            &[vec![rating(1e9, 50.0)], vec![rating(0.0, 50.0)]],
+            &record(vec![1, 0]),
+        );
+
+        for after in [lonely[0][0], mismatch[0][0], mismatch[1][0]] {
+            assert!(after.value.is_finite() && after.volatility.is_finite());
+            assert_eq!(after.volatility, INITIAL_VOLATILITY);
+        }
+        assert_eq!(lonely[0][0].value, 1500.0);
+        assert!(lonely[0][0].deviation > 200.0);
+        assert_eq!(mismatch[1][0].value, 0.0);
+    }
+
+    #[test]
+    fn trueskill_winners_gain_and_grow_more_certain() {
+        let before = vec![
+            vec![rating(1000.0, 350.0), rating(1000.0, 350.0)],
+            vec![rating(1000.0, 350.0), rating(1000.0, 350.0)],
+        ];
+        let after = trueskill(&before, &record(vec![3, 1]));
+        assert!(after[0][0].value > 1000.0);
+        assert!(after[1][0].value < 1000.0);
+        assert!(after[0][0].deviation < 350.0);
+        assert!((after[0][0].value - 1000.0 + after[1][0].value - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ratings_start_from_skill_and_skip_incomplete_records() {
+        let teams = vec![vec![player(1200)], vec![player(800)]];
+        let mut ratings = Ratings::new(RatingModel::Elo);
+
+        ratings.record(&MatchRecord::new(1, &teams, vec![1]));
+        assert_eq!(ratings.get(teams[0][0].id), None);
+
+        ratings.record(&MatchRecord::new(1, &teams, vec![0, 1]));
+        assert!(ratings.get(teams[0][0].id).unwrap().skill() < 1200);
+        assert!(ratings.get(teams[1][0].id).unwrap().skill() > 800);
+
+        for model in RatingModel::ALL {
+            let matches = [
+                MatchRecord::new(1, &teams, vec![0, 1]),
+                MatchRecord::new(2, &teams, vec![2, 2]),
+            ];
+            let ratings = Ratings::from_matches(model, &matches);
+            let rating = ratings.get(teams[1][0].id).unwrap();
+            assert!(
+                rating.value.is_finite() && rating.deviation.is_finite(),
+                "{:?}",
+                model
+            );
+        }
+    }
+}
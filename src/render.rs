@@ -0,0 +1,384 @@
+//! Drawing teams and QR codes into images, for posting where text loses its formatting.
+
+use crate::balancer::{Player, sum_skill};
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use image::{ImageEncoder, ImageError, Rgba, RgbaImage};
+use qrcode::types::QrError;
+use qrcode::{Color, EcLevel, QrCode};
+
+/// The font the app uses.
+const FONT: &[u8] = include_bytes!("../assets/Stratum2WebMedium.otf");
+
+/// Header colors, the same as the spin wheel's, repeated when there are more teams.
+const TEAM_COLORS: [Rgba<u8>; 4] = [
+    Rgba([51, 105, 232, 255]),
+    Rgba([213, 15, 37, 255]),
+    Rgba([238, 178, 17, 255]),
+    Rgba([0, 153, 37, 255]),
+];
+const BENCH_COLOR: Rgba<u8> = Rgba([90, 90, 90, 255]);
+const BACKGROUND: Rgba<u8> = Rgba([27, 27, 27, 255]);
+const CARD: Rgba<u8> = Rgba([40, 40, 40, 255]);
+const TEXT: Rgba<u8> = Rgba([240, 240, 240, 255]);
+const WEAK_TEXT: Rgba<u8> = Rgba([150, 150, 150, 255]);
+const STAR: Rgba<u8> = Rgba([255, 200, 40, 255]);
+
+const HEADER_SIZE: f32 = 30.0;
+const ROW_SIZE: f32 = 24.0;
+const HEADER_HEIGHT: u32 = 48;
+const ROW_HEIGHT: u32 = 34;
+const PADDING: u32 = 16;
+const GAP: u32 = 16;
+const MIN_COLUMN_WIDTH: u32 = 180;
+/// Light border around a QR code, in modules, that readers need to find it.
+const QR_QUIET_ZONE: u32 = 4;
+
+/// One card of the image: a team or the bench.
+struct Column<'a> {
+    title: String,
+    total: Option<u32>,
+    color: Rgba<u8>,
+    players: &'a [Player],
+}
+
+/// Teams side by side, each under a colored header with its skill total, and the bench after
+/// them. Captains get a star. Skills are left out when `hide_skills` is set.
+pub fn teams_image(teams: &[Vec<Player>], bench: &[Player], hide_skills: bool) -> RgbaImage {
+    let font = FontRef::try_from_slice(FONT).expect("The bundled font is valid");
+    let header_font = font.as_scaled(PxScale::from(HEADER_SIZE));
+    let row_font = font.as_scaled(PxScale::from(ROW_SIZE));
+
+    let mut columns: Vec<Column> = teams
+        .iter()
+        .enumerate()
+        .map(|(team_idx, team)| Column {
+            title: format!("Team {}", team_idx + 1),
+            total: (!hide_skills).then(|| sum_skill(team)),
+            color: TEAM_COLORS[team_idx % TEAM_COLORS.len()],
+            players: team,
+        })
+        .collect();
+    if !bench.is_empty() {
+        columns.push(Column {
+            title: "Bench".to_owned(),
+            total: None,
+            color: BENCH_COLOR,
+            players: bench,
+        });
+    }
+
+    let star_width = ROW_HEIGHT / 2 + PADDING / 2;
+    let widths: Vec<u32> = columns
+        .iter()
+        .map(|column| {
+            let total = column.total.map_or(0.0, |total| {
+                text_width(&header_font, &total.to_string()) + GAP as f32
+            });
+            let header = text_width(&header_font, &column.title) + total;
+            let rows = column.players.iter().map(|player| {
+                let skill = if hide_skills {
+                    0.0
+                } else {
+                    text_width(&row_font, &player.skill.to_string()) + GAP as f32
+                };
+                star_width as f32 + text_width(&row_font, &player.name) + skill
+            });
+            let widest = rows.fold(header, f32::max).ceil() as u32;
+            (widest + 2 * PADDING).max(MIN_COLUMN_WIDTH)
+        })
+        .collect();
+
+    let tallest = columns.iter().map(|c| c.players.len()).max().unwrap_or(0) as u32;
+    let card_height = HEADER_HEIGHT + tallest * ROW_HEIGHT + PADDING;
+    let width = widths.iter().sum::<u32>() + GAP * (widths.len() as u32 + 1);
+    let height = card_height + 2 * GAP;
+
+    let mut image = RgbaImage::from_pixel(width.max(1), height, BACKGROUND);
+
+    let mut x = GAP;
+    for (column, column_width) in columns.iter().zip(widths) {
+        fill_rect(&mut image, x, GAP, column_width, card_height, CARD);
+        fill_rect(
+            &mut image,
+            x,
+            GAP,
+            column_width,
+            HEADER_HEIGHT,
+            column.color,
+        );
+
+        let header_baseline = GAP as f32 + (HEADER_HEIGHT as f32 + header_font.ascent()) / 2.0;
+        draw_text(
+            &mut image,
+            &header_font,
+            &column.title,
+            (x + PADDING) as f32,
+            header_baseline,
+            TEXT,
+        );
+        if let Some(total) = column.total {
+            let total = total.to_string();
+            let right = (x + column_width - PADDING) as f32;
+            let total_x = right - text_width(&header_font, &total);
+            draw_text(
+                &mut image,
+                &header_font,
+                &total,
+                total_x,
+                header_baseline,
+                TEXT,
+            );
+        }
+
+        for (row, player) in column.players.iter().enumerate() {
+            let top = GAP + HEADER_HEIGHT + PADDING / 2 + row as u32 * ROW_HEIGHT;
+            let baseline = top as f32 + (ROW_HEIGHT as f32 + row_font.ascent()) / 2.0;
+
+            if player.is_captain {
+                let center = (
+                    (x + PADDING) as f32 + ROW_HEIGHT as f32 / 4.0,
+                    top as f32 + ROW_HEIGHT as f32 / 2.0,
+                );
+                draw_star(&mut image, center, ROW_HEIGHT as f32 / 3.0, STAR);
+            }
+            let name_x = (x + PADDING + star_width) as f32;
+            draw_text(&mut image, &row_font, &player.name, name_x, baseline, TEXT);
+
+            if !hide_skills {
+                let skill = player.skill.to_string();
+                let right = (x + column_width - PADDING) as f32;
+                let skill_x = right - text_width(&row_font, &skill);
+                draw_text(&mut image, &row_font, &skill, skill_x, baseline, WEAK_TEXT);
+            }
+        }
+
+        x += column_width + GAP;
+    }
+
+    image
+}
+
+/// [`teams_image`] encoded as a PNG file.
+pub fn teams_png(
+    teams: &[Vec<Player>],
+    bench: &[Player],
+    hide_skills: bool,
+) -> Result<Vec<u8>, ImageError> {
+    encode_png(&teams_image(teams, bench, hide_skills))
+}
+
+/// `text` as a black on white QR code, `module_size` pixels to a module. Uses the lowest error
+/// correction, to fit as much text as possible.
+pub fn qr_code(text: &str, module_size: u32) -> Result<RgbaImage, QrError> {
+    let code = QrCode::with_error_correction_level(text, EcLevel::L)?;
+    let modules = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (modules + 2 * QR_QUIET_ZONE) * module_size;
+
+    let mut image = RgbaImage::from_pixel(size, size, Rgba([255, 255, 255, 255]));
+    for (idx, color) in colors.iter().enumerate() {
+        if *color == Color::Dark {
+            let x = (idx as u32 % modules + QR_QUIET_ZONE) * module_size;
+            let y = (idx as u32 / modules + QR_QUIET_ZONE) * module_size;
+            fill_rect(
+                &mut image,
+                x,
+                y,
+                module_size,
+                module_size,
+                Rgba([0, 0, 0, 255]),
+            );
+        }
+    }
+    Ok(image)
+}
+
+/// `image` as the bytes of a PNG file.
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, ImageError> {
+    let mut bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut bytes).write_image(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+        image::ExtendedColorType::Rgba8,
+    )?;
+    Ok(bytes)
+}
+
+fn text_width<F: Font>(font: &impl ScaleFont<F>, text: &str) -> f32 {
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let glyph = font.glyph_id(c);
+        if let Some(previous) = previous {
+            width += font.kern(previous, glyph);
+        }
+        width += font.h_advance(glyph);
+        previous = Some(glyph);
+    }
+    width
+}
+
+/// Draws `text` starting at `x`, with its baseline at `baseline`.
+fn draw_text<F: Font>(
+    image: &mut RgbaImage,
+    font: &impl ScaleFont<F>,
+    text: &str,
+    x: f32,
+    baseline: f32,
+    color: Rgba<u8>,
+) {
+    let mut caret = x;
+    let mut previous = None;
+    for c in text.chars() {
+        let glyph_id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += font.kern(previous, glyph_id);
+        }
+        let glyph = glyph_id.with_scale_and_position(font.scale(), point(caret, baseline));
+        caret += font.h_advance(glyph_id);
+        previous = Some(glyph_id);
+
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i64 + gx as i64;
+            let py = bounds.min.y as i64 + gy as i64;
+            blend(image, px, py, color, coverage);
+        });
+    }
+}
+
+fn fill_rect(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
+
+/// A five pointed star, smoothed by sampling each pixel four times.
+fn draw_star(image: &mut RgbaImage, center: (f32, f32), radius: f32, color: Rgba<u8>) {
+    let points: Vec<(f32, f32)> = (0..10)
+        .map(|i| {
+            let angle = std::f32::consts::PI * (i as f32 / 5.0 - 0.5);
+            let r = if i % 2 == 0 { radius } else { radius * 0.45 };
+            (center.0 + r * angle.cos(), center.1 + r * angle.sin())
+        })
+        .collect();
+
+    let min_x = (center.0 - radius).floor() as i64;
+    let max_x = (center.0 + radius).ceil() as i64;
+    let min_y = (center.1 - radius).floor() as i64;
+    let max_y = (center.1 + radius).ceil() as i64;
+    const SAMPLES: [(f32, f32); 4] = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)];
+
+    for py in min_y..=max_y {
+        for px in min_x..=max_x {
+            let inside = SAMPLES
+                .iter()
+                .filter(|(dx, dy)| contains(&points, (px as f32 + dx, py as f32 + dy)))
+                .count();
+            if inside > 0 {
+                blend(image, px, py, color, inside as f32 / SAMPLES.len() as f32);
+            }
+        }
+    }
+}
+
+/// Even-odd test of whether `point` is inside `polygon`.
+fn contains(polygon: &[(f32, f32)], point: (f32, f32)) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &current in polygon {
+        if (current.1 > point.1) != (previous.1 > point.1) {
+            let x = current.0
+                + (point.1 - current.1) / (previous.1 - current.1) * (previous.0 - current.0);
+            if point.0 < x {
+                inside = !inside;
+            }
+        }
+        previous = current;
+    }
+    inside
+}
+
+/// Mixes `color` into the pixel at `x`, `y` by `coverage`, if it is inside the image.
+fn blend(image: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>, coverage: f32) {
+    if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 {
+        return;
+    }
+    let coverage = coverage.clamp(0.0, 1.0);
+    let pixel = image.get_pixel_mut(x as u32, y as u32);
+    for channel in 0..3 {
+        let under = pixel.0[channel] as f32;
+        let over = color.0[channel] as f32;
+        pixel.0[channel] = (under + (over - under) * coverage).round() as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name: &str, skill: u32, is_captain: bool) -> Player {
+        Player {
+            name: name.to_owned(),
+            skill,
+            is_captain,
+            ..Default::default()
+        }
+    }
+
+    fn teams(skill: u32) -> Vec<Vec<Player>> {
+        vec![
+            vec![player("Ann", skill, true), player("Bob", skill, false)],
+            vec![player("Cat", skill, false)],
+        ]
+    }
+
+    #[test]
+    fn image_fits_every_column_and_row() {
+        let bench = [player("Dan", 1, false)];
+        let image = teams_image(&teams(1), &bench, false);
+
+        // Two players in the tallest column
+        assert_eq!(
+            image.height(),
+            HEADER_HEIGHT + 2 * ROW_HEIGHT + PADDING + 2 * GAP
+        );
+        // Three cards of at least the minimum width, with gaps around them
+        assert!(image.width() >= 3 * MIN_COLUMN_WIDTH + 4 * GAP);
+        assert!(teams_image(&teams(1), &[], false).width() < image.width());
+    }
+
+    #[test]
+    fn hidden_skills_are_not_drawn() {
+        let low = teams_image(&teams(1), &[], true);
+        let high = teams_image(&teams(34999), &[], true);
+        assert_eq!(low, high);
+
+        let shown = teams_image(&teams(34999), &[], false);
+        assert_ne!(shown, high);
+    }
+
+    #[test]
+    fn qr_code_has_a_quiet_zone() {
+        let code = QrCode::with_error_correction_level("https://example.com", EcLevel::L).unwrap();
+        let image = qr_code("https://example.com", 3).unwrap();
+        let size = (code.width() as u32 + 2 * QR_QUIET_ZONE) * 3;
+        assert_eq!((image.width(), image.height()), (size, size));
+        assert_eq!(*image.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
+        // The top left finder pattern starts right after the quiet zone
+        let corner = QR_QUIET_ZONE * 3;
+        assert_eq!(*image.get_pixel(corner, corner), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn png_has_the_png_signature() {
+        let png = teams_png(&teams(1), &[], false).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+}
@@ -0,0 +1,309 @@
+//! The format of the app's save files: the saved data along with the version of its format, so
+//! files saved by older versions of the app can be upgraded instead of thrown away.
+
+use ron::value::RawValue;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// One step of upgrading saved data, turning the RON of one format version into the RON of the
+/// next one.
+pub type Migration = fn(&str) -> Result<String, String>;
+
+/// Version of files saved before versions were recorded. Their format is that of version 1.
+pub const UNVERSIONED: u32 = 1;
+
+/// Upgrades of the Team Creator's save file, for reading it as `T`. The app reads all of it and
+/// the command line only the part it needs, both through the same versions.
+///
+/// Version 2 has an id for every player. Players saved before ids existed get theirs when the
+/// file is upgraded.
+pub fn team_creator_migrations<T: Serialize + DeserializeOwned>() -> &'static [Migration] {
+    const { &[resave::<T> as Migration] }
+}
+
+/// A step for formats that only gained fields with defaults: the data is read as `T`, filling the
+/// defaults in, and written back with them.
+pub fn resave<T: Serialize + DeserializeOwned>(ron: &str) -> Result<String, String> {
+    let data: T = ron::from_str(ron).map_err(|error| error.to_string())?;
+    ron::to_string(&data).map_err(|error| error.to_string())
+}
+
+#[derive(serde::Serialize)]
+struct SaveFile<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(serde::Deserialize)]
+struct RawSaveFile<'a> {
+    version: u32,
+    #[serde(borrow)]
+    data: &'a RawValue,
+}
+
+/// Why saved data could not be read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadError {
+    /// The file was saved by a newer version of the app.
+    TooNew { version: u32, supported: u32 },
+    /// One of the migrations failed.
+    Migration { from: u32, error: String },
+    /// The data doesn't match what is expected of the current version.
+    Invalid(String),
+    /// The file starts like a versioned save file but isn't one, so it was cut short or damaged.
+    Corrupt(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::TooNew { version, supported } => write!(
+                f,
+                "it was saved by a newer version of the app (format {}, this one reads up to {})",
+                version, supported
+            ),
+            LoadError::Migration { from, error } => {
+                write!(f, "upgrading it from format {} failed: {}", from, error)
+            }
+            LoadError::Invalid(error) => write!(f, "{}", error),
+            LoadError::Corrupt(error) => write!(f, "the file is damaged: {}", error),
+        }
+    }
+}
+
+/// Version written by an app that upgrades older files with `migrations`.
+pub fn current_version(migrations: &[Migration]) -> u32 {
+    UNVERSIONED + migrations.len() as u32
+}
+
+/// `data` as the contents of a save file of format `version`.
+pub fn to_string<T: Serialize>(data: &T, version: u32) -> Result<String, ron::Error> {
+    ron::to_string(&SaveFile { version, data })
+}
+
+/// Format version and data of a save file. Files saved before versions were recorded are all
+/// data.
+pub fn split(text: &str) -> Result<(u32, &str), LoadError> {
+    match ron::from_str::<RawSaveFile>(text) {
+        Ok(file) => Ok((file.version, file.data.get_ron())),
+        Err(error) if is_versioned(text) => Err(LoadError::Corrupt(error.to_string())),
+        Err(_) => Ok((UNVERSIONED, text)),
+    }
+}
+
+/// Whether `text` starts like a versioned save file, `(version:`.
+fn is_versioned(text: &str) -> bool {
+    text.trim_start()
+        .strip_prefix('(')
+        .and_then(|rest| rest.trim_start().strip_prefix("version"))
+        .is_some_and(|rest| rest.trim_start().starts_with(':'))
+}
+
+/// Reads a save file, upgrading it first if it is of an older format. `migrations` has a step
+/// for each version after [`UNVERSIONED`], the first one upgrading to version 2.
+pub fn from_str<T: DeserializeOwned>(text: &str, migrations: &[Migration]) -> Result<T, LoadError> {
+    let supported = current_version(migrations);
+    let (version, data) = split(text)?;
+    if version > supported {
+        return Err(LoadError::TooNew { version, supported });
+    }
+
+    let done = version
+        .checked_sub(UNVERSIONED)
+        .ok_or_else(|| LoadError::Invalid(format!("there is no format {}", version)))?;
+
+    let mut data = data.to_owned();
+    for (from, migration) in (version..).zip(&migrations[done as usize..]) {
+        data = migration(&data).map_err(|error| LoadError::Migration { from, error })?;
+    }
+
+    ron::from_str(&data).map_err(|error| LoadError::Invalid(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Old {
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct New {
+        names: Vec<String>,
+    }
+
+    /// Turns `(name:"x")` into `(names:["x"])`.
+    fn to_names(ron: &str) -> Result<String, String> {
+        let old: Old = ron::from_str(ron).map_err(|e| e.to_string())?;
+        ron::to_string(&New {
+            names: vec![old.name],
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    fn fail(_: &str) -> Result<String, String> {
+        Err("no way".to_owned())
+    }
+
+    #[test]
+    fn round_trip() {
+        let old = Old {
+            name: "Ann".to_owned(),
+        };
+        let text = to_string(&old, 1).unwrap();
+        assert_eq!(text, "(version:1,data:(name:\"Ann\"))");
+        assert_eq!(split(&text), Ok((1, "(name:\"Ann\")")));
+        assert_eq!(from_str::<Old>(&text, &[]), Ok(old));
+    }
+
+    #[test]
+    fn legacy_files_are_all_data() {
+        let text = "(name:\"Ann\")";
+        assert_eq!(split(text), Ok((UNVERSIONED, text)));
+        assert_eq!(
+            from_str::<Old>(text, &[]),
+            Ok(Old {
+                name: "Ann".to_owned()
+            })
+        );
+        // And upgraded like any file of the first format
+        assert_eq!(
+            from_str::<New>(text, &[to_names]),
+            Ok(New {
+                names: vec!["Ann".to_owned()]
+            })
+        );
+    }
+
+    #[test]
+    fn older_formats_are_migrated() {
+        let migrations: [Migration; 1] = [to_names];
+        assert_eq!(current_version(&migrations), 2);
+
+        let old = to_string(
+            &Old {
+                name: "Ann".to_owned(),
+            },
+            1,
+        )
+        .unwrap();
+        let expected = New {
+            names: vec!["Ann".to_owned()],
+        };
+        assert_eq!(from_str::<New>(&old, &migrations), Ok(expected));
+
+        // Files already in the current format are left alone
+        let new = to_string(&New { names: Vec::new() }, 2).unwrap();
+        assert_eq!(
+            from_str::<New>(&new, &migrations),
+            Ok(New { names: Vec::new() })
+        );
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct WithId {
+        name: String,
+        #[serde(default = "next_id")]
+        id: u32,
+    }
+
+    fn next_id() -> u32 {
+        7
+    }
+
+    #[test]
+    fn resaving_keeps_the_defaults() {
+        let migrations = team_creator_migrations::<WithId>();
+        assert_eq!(current_version(migrations), 2);
+
+        let upgraded = migrations[0]("(name:\"Ann\")").unwrap();
+        assert_eq!(upgraded, "(name:\"Ann\",id:7)");
+        assert_eq!(
+            from_str::<WithId>("(name:\"Ann\",id:3)", migrations),
+            Ok(WithId {
+                name: "Ann".to_owned(),
+                id: 3
+            })
+        );
+        assert!(migrations[0]("(nom:1)").is_err());
+    }
+
+    #[test]
+    fn damaged_files_are_corrupt() {
+        let text = to_string(
+            &Old {
+                name: "Ann".to_owned(),
+            },
+            1,
+        )
+        .unwrap();
+        for damaged in [
+            &text[..text.len() - 4],
+            "( version : 2, data: (name:\"Ann\"",
+            "(version:x)",
+        ] {
+            assert!(
+                matches!(from_str::<Old>(damaged, &[]), Err(LoadError::Corrupt(_))),
+                "{}",
+                damaged
+            );
+        }
+        // Unversioned data that is damaged is only invalid
+        assert!(matches!(
+            from_str::<Old>("(name:\"Ann\"", &[]),
+            Err(LoadError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn unreadable_files_are_errors() {
+        let newer = to_string(
+            &Old {
+                name: String::new(),
+            },
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            from_str::<Old>(&newer, &[to_names]),
+            Err(LoadError::TooNew {
+                version: 3,
+                supported: 2
+            })
+        );
+
+        let old = to_string(
+            &Old {
+                name: String::new(),
+            },
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            from_str::<New>(&old, &[fail]),
+            Err(LoadError::Migration {
+                from: 1,
+                error: "no way".to_owned()
+            })
+        );
+
+        let zero = to_string(
+            &Old {
+                name: String::new(),
+            },
+            0,
+        )
+        .unwrap();
+        assert!(matches!(
+            from_str::<Old>(&zero, &[]),
+            Err(LoadError::Invalid(_))
+        ));
+        assert!(matches!(
+            from_str::<New>("(name:\"Ann\")", &[]),
+            Err(LoadError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// Seed for everything random in a result, so it can be reproduced by entering the same seed
+/// with the same inputs. Shown and parsed as 8 hexadecimal digits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Seed(pub u32);
+
+impl Seed {
+    pub fn random() -> Self {
+        Seed(rand::random())
+    }
+
+    /// A fresh generator, always producing the same numbers for the same seed. Its algorithm is
+    /// pinned, unlike `StdRng`'s, so seeds keep working across versions of rand.
+    pub fn rng(self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.0 as u64)
+    }
+
+    /// The entered seed, a random one when `input` is blank, or an error if it isn't valid.
+    pub fn from_input(input: &str) -> Result<Self, ParseIntError> {
+        if input.trim().is_empty() {
+            Ok(Seed::random())
+        } else {
+            input.parse()
+        }
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08X}", self.0)
+    }
+}
+
+impl FromStr for Seed {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str_radix(s.trim(), 16).map(Seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balancer::{Metric, Player, SplitOptions, best_balanced_split};
+    use rand::Rng;
+
+    #[test]
+    fn seeds_are_shown_and_parsed_as_hex() {
+        assert_eq!(Seed(0xABCD).to_string(), "0000ABCD");
+        assert_eq!(" 0000abcd ".parse(), Ok(Seed(0xABCD)));
+        assert!("xyz".parse::<Seed>().is_err());
+        assert_eq!(Seed::from_input("1F"), Ok(Seed(0x1F)));
+    }
+
+    /// Changing what a seed produces breaks every seed users wrote down.
+    #[test]
+    fn numbers_are_pinned() {
+        let mut rng = Seed(0xABCD).rng();
+        let numbers: Vec<u32> = (0..4).map(|_| rng.random()).collect();
+        assert_eq!(numbers, [4142113625, 3373831677, 939169591, 2029055893]);
+    }
+
+    #[test]
+    fn teams_are_pinned() {
+        // More players than the exact solver takes, so the split is down to the seed
+        let mut players: Vec<Player> = (0..16)
+            .map(|idx| Player {
+                name: format!("P{}", idx),
+                skill: idx * 377 % 2000,
+                ..Default::default()
+            })
+            .collect();
+        let options = SplitOptions {
+            team_count: 2,
+            team_sizes: &[],
+            short_handed_bonus: 0,
+            attributes: &[],
+            roles: &[],
+            constraints: &[],
+            history: &[],
+            fresh_teams_weight: 0,
+            metric: Metric::default(),
+            candidates: 1,
+        };
+        let splits = best_balanced_split(&mut players, &options, &mut Seed(0xABCD).rng()).unwrap();
+        let names: Vec<Vec<&str>> = splits[0]
+            .teams
+            .iter()
+            .map(|team| team.iter().map(|p| p.name.as_str()).collect())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ["P3", "P2", "P14", "P6", "P9", "P1", "P7", "P10"],
+                ["P11", "P5", "P12", "P0", "P13", "P8", "P4", "P15"]
+            ]
+        );
+    }
+}
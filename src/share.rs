@@ -0,0 +1,266 @@
+//! Rosters and wheels packed into the fragment of a link, so they can be sent to someone else.
+
+use crate::balancer::{Attribute, MAX_SKILL, Player, Role};
+use crate::wheel::Choice;
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use miniz_oxide::inflate::TINFLStatus;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use ulid::Ulid;
+
+/// Start of the fragment of a share link, after the `#`.
+pub const FRAGMENT_PREFIX: &str = "share=";
+/// Most bytes a share link may unpack to. Far more than any real roster, but keeps a crafted link
+/// from using up the memory.
+const MAX_DECODED_SIZE: usize = 1024 * 1024;
+
+/// What a share link carries.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum Shared {
+    Roster {
+        name: String,
+        players: Vec<SharedPlayer>,
+        attributes: Vec<Attribute>,
+        roles: Vec<Role>,
+    },
+    Wheel {
+        choices: Vec<Choice>,
+    },
+}
+
+impl Shared {
+    /// What was shared, like `roster "Tuesday" with 10 players`.
+    pub fn describe(&self) -> String {
+        match self {
+            Shared::Roster { name, players, .. } => {
+                format!(
+                    "roster \"{}\" with {}",
+                    name,
+                    count(players.len(), "player", "players")
+                )
+            }
+            Shared::Wheel { choices } => {
+                format!(
+                    "spin wheel with {}",
+                    count(choices.len(), "entry", "entries")
+                )
+            }
+        }
+    }
+}
+
+fn count(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+/// A player of a shared roster. Only what is needed to make teams is sent, notes, tags and when
+/// they were last benched stay with the roster's owner.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SharedPlayer {
+    pub name: String,
+    pub skill: u32,
+    pub is_captain: bool,
+    pub attributes: BTreeMap<Ulid, u32>,
+    pub roles: BTreeSet<Ulid>,
+}
+
+impl From<&Player> for SharedPlayer {
+    fn from(player: &Player) -> Self {
+        SharedPlayer {
+            name: player.name.clone(),
+            skill: player.skill,
+            is_captain: player.is_captain,
+            attributes: player.attributes.clone(),
+            roles: player.roles.clone(),
+        }
+    }
+}
+
+impl SharedPlayer {
+    /// A new player of the roster it is imported into. Values over [`MAX_SKILL`], which the app
+    /// never shares, are lowered to it.
+    pub fn to_player(&self) -> Player {
+        Player {
+            name: self.name.clone(),
+            skill: self.skill.min(MAX_SKILL),
+            is_captain: self.is_captain,
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(&id, &value)| (id, value.min(MAX_SKILL)))
+                .collect(),
+            roles: self.roles.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Why a share link could not be read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShareError {
+    /// The text is not URL-safe base64.
+    Encoding,
+    /// The data does not decompress, so the link was probably cut short.
+    Compression,
+    /// The data unpacks to more than [`MAX_DECODED_SIZE`] bytes.
+    TooLarge,
+    /// The data is not a roster or wheel, maybe from a newer version of the app.
+    Contents(String),
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareError::Encoding => write!(f, "the link contains characters it shouldn't"),
+            ShareError::Compression => write!(f, "the link seems to be cut short"),
+            ShareError::TooLarge => write!(f, "the link holds more than a roster ever would"),
+            ShareError::Contents(error) => write!(f, "the shared data is not valid: {}", error),
+        }
+    }
+}
+
+/// `shared` compressed into URL-safe text.
+pub fn encode(shared: &Shared) -> String {
+    let ron = ron::to_string(shared).expect("Shared data can always be written as RON");
+    let compressed = miniz_oxide::deflate::compress_to_vec(ron.as_bytes(), 9);
+    URL_SAFE_NO_PAD.encode(compressed)
+}
+
+/// Reads text made by [`encode`].
+pub fn decode(text: &str) -> Result<Shared, ShareError> {
+    let compressed = URL_SAFE_NO_PAD
+        .decode(text.trim())
+        .map_err(|_| ShareError::Encoding)?;
+    let ron = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_DECODED_SIZE)
+        .map_err(|error| match error.status {
+            TINFLStatus::HasMoreOutput => ShareError::TooLarge,
+            _ => ShareError::Compression,
+        })?;
+    let ron = String::from_utf8(ron).map_err(|_| ShareError::Compression)?;
+    ron::from_str(&ron).map_err(|error| ShareError::Contents(error.to_string()))
+}
+
+/// Fragment for a link sharing `shared`, including the `#`.
+pub fn to_fragment(shared: &Shared) -> String {
+    format!("#{}{}", FRAGMENT_PREFIX, encode(shared))
+}
+
+/// What the fragment of a page's address shares, or `None` if it isn't a share link.
+pub fn from_fragment(fragment: &str) -> Option<Result<Shared, ShareError>> {
+    let text = fragment
+        .strip_prefix('#')
+        .unwrap_or(fragment)
+        .strip_prefix(FRAGMENT_PREFIX)?;
+    Some(decode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roster() -> Shared {
+        let attribute = Attribute::new("Speed".to_owned());
+        let player = Player {
+            name: "Ann".to_owned(),
+            skill: 1200,
+            is_captain: true,
+            attributes: BTreeMap::from([(attribute.id, 3)]),
+            last_benched: Some(4),
+            tags: vec!["new".to_owned()],
+            notes: "Owes 5€".to_owned(),
+            ..Default::default()
+        };
+        Shared::Roster {
+            name: "Tuesday".to_owned(),
+            players: vec![SharedPlayer::from(&player)],
+            attributes: vec![attribute],
+            roles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn roster_round_trip() {
+        let fragment = to_fragment(&roster());
+        assert!(fragment.starts_with("#share="));
+        let Some(Ok(Shared::Roster {
+            name,
+            players,
+            attributes,
+            ..
+        })) = from_fragment(&fragment)
+        else {
+            panic!("Not a roster");
+        };
+
+        assert_eq!(name, "Tuesday");
+        let player = players[0].to_player();
+        assert_eq!((player.name.as_str(), player.skill), ("Ann", 1200));
+        assert!(player.is_captain);
+        assert_eq!(player.attribute(attributes[0].id), 3);
+        assert_eq!(player.last_benched, None);
+        assert!(player.tags.is_empty() && player.notes.is_empty());
+    }
+
+    #[test]
+    fn private_fields_are_not_sent() {
+        let ron = ron::to_string(&roster()).unwrap();
+        assert!(!ron.contains("Owes") && !ron.contains("new"));
+    }
+
+    #[test]
+    fn oversized_values_are_lowered() {
+        let id = Ulid::new();
+        let shared = SharedPlayer {
+            name: "Ann".to_owned(),
+            skill: 3_000_000_000,
+            is_captain: false,
+            attributes: BTreeMap::from([(id, u32::MAX)]),
+            roles: BTreeSet::new(),
+        };
+        let player = shared.to_player();
+        assert_eq!(player.skill, MAX_SKILL);
+        assert_eq!(player.attribute(id), MAX_SKILL);
+    }
+
+    #[test]
+    fn wheel_round_trip() {
+        let shared = Shared::Wheel {
+            choices: vec![Choice::new("Pizza".to_owned(), Some(3))],
+        };
+        let Ok(Shared::Wheel { choices }) = decode(&encode(&shared)) else {
+            panic!("Not a wheel");
+        };
+        assert_eq!((choices[0].label.as_str(), choices[0].weight), ("Pizza", 3));
+    }
+
+    #[test]
+    fn describe_counts() {
+        assert_eq!(roster().describe(), "roster \"Tuesday\" with 1 player");
+        let wheel = |n| Shared::Wheel {
+            choices: vec![Choice::new("A".to_owned(), None); n],
+        };
+        assert_eq!(wheel(1).describe(), "spin wheel with 1 entry");
+        assert_eq!(wheel(0).describe(), "spin wheel with 0 entries");
+    }
+
+    #[test]
+    fn broken_links_are_errors() {
+        assert!(from_fragment("#other=1").is_none());
+        assert_eq!(decode("not base64!").unwrap_err(), ShareError::Encoding);
+
+        let compressed = URL_SAFE_NO_PAD.decode(encode(&roster())).unwrap();
+        let cut_short = URL_SAFE_NO_PAD.encode(&compressed[..compressed.len() / 2]);
+        assert_eq!(decode(&cut_short).unwrap_err(), ShareError::Compression);
+
+        let huge = miniz_oxide::deflate::compress_to_vec(&vec![b' '; MAX_DECODED_SIZE + 1], 9);
+        assert_eq!(
+            decode(&URL_SAFE_NO_PAD.encode(huge)).unwrap_err(),
+            ShareError::TooLarge
+        );
+        assert!(matches!(
+            decode(&URL_SAFE_NO_PAD.encode(miniz_oxide::deflate::compress_to_vec(b"(", 9))),
+            Err(ShareError::Contents(_))
+        ));
+    }
+}
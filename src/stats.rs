@@ -0,0 +1,217 @@
+//! Per-player statistics gathered from the match log.
+
+use crate::rating::{MatchRecord, RatingModel, Ratings};
+use std::collections::HashMap;
+use ulid::Ulid;
+
+/// Everything known about a player from the games they played.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerStats {
+    pub id: Ulid,
+    /// Name in the player's most recent game.
+    pub name: String,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Games played as captain.
+    pub captain_games: u32,
+    pub captain_wins: u32,
+    /// Rating after each game, as game number and skill. Starts with the skill of the first
+    /// game, before it was played.
+    pub skill_history: Vec<(u32, u32)>,
+    /// Games played on the same team as each other player.
+    pub teammates: HashMap<Ulid, u32>,
+    /// Games played against each other player.
+    pub opponents: HashMap<Ulid, u32>,
+}
+
+impl PlayerStats {
+    fn new(id: Ulid) -> Self {
+        PlayerStats {
+            id,
+            ..Default::default()
+        }
+    }
+
+    /// Share of games won, counting draws as half a win. `None` before the first game.
+    pub fn win_rate(&self) -> Option<f32> {
+        (self.games > 0).then(|| (self.wins as f32 + self.draws as f32 / 2.0) / self.games as f32)
+    }
+
+    /// Rating after the most recent game.
+    pub fn skill(&self) -> u32 {
+        self.skill_history.last().map_or(0, |&(_, skill)| skill)
+    }
+
+    /// Players most often on this player's team, most frequent first.
+    pub fn top_teammates(&self, count: usize) -> Vec<(Ulid, u32)> {
+        most_frequent(&self.teammates, count)
+    }
+
+    /// Players most often on an opposing team, most frequent first.
+    pub fn top_opponents(&self, count: usize) -> Vec<(Ulid, u32)> {
+        most_frequent(&self.opponents, count)
+    }
+}
+
+/// Stats of everyone in the match log, in the order they first played. Ratings are replayed with
+/// `model` to follow each player's skill over time.
+pub fn player_stats(matches: &[MatchRecord], model: RatingModel) -> Vec<PlayerStats> {
+    let mut stats: Vec<PlayerStats> = Vec::new();
+    let mut index: HashMap<Ulid, usize> = HashMap::new();
+    let mut ratings = Ratings::new(model);
+
+    for record in matches {
+        if record.teams.len() < 2 || record.scores.len() != record.teams.len() {
+            continue;
+        }
+        ratings.record(record);
+        let winners = record.winners();
+        let draw = record.is_draw();
+
+        for (team_idx, team) in record.teams.iter().enumerate() {
+            for player in team {
+                let idx = *index.entry(player.id).or_insert_with(|| {
+                    stats.push(PlayerStats::new(player.id));
+                    stats.len() - 1
+                });
+                let entry = &mut stats[idx];
+
+                entry.name = player.name.clone();
+                if entry.skill_history.is_empty() {
+                    entry.skill_history.push((record.game, player.skill));
+                }
+                if let Some(rating) = ratings.get(player.id) {
+                    entry.skill_history.push((record.game, rating.skill()));
+                }
+
+                entry.games += 1;
+                let won = !draw && winners.contains(&team_idx);
+                if draw {
+                    entry.draws += 1;
+                } else if won {
+                    entry.wins += 1;
+                } else {
+                    entry.losses += 1;
+                }
+                if player.is_captain {
+                    entry.captain_games += 1;
+                    entry.captain_wins += u32::from(won);
+                }
+
+                for (other_idx, other_team) in record.teams.iter().enumerate() {
+                    let counts = if other_idx == team_idx {
+                        &mut entry.teammates
+                    } else {
+                        &mut entry.opponents
+                    };
+                    for other in other_team.iter().filter(|p| p.id != player.id) {
+                        *counts.entry(other.id).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    stats
+}
+
+fn most_frequent(counts: &HashMap<Ulid, u32>, count: usize) -> Vec<(Ulid, u32)> {
+    let mut sorted: Vec<(Ulid, u32)> = counts.iter().map(|(&id, &n)| (id, n)).collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    sorted.truncate(count);
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balancer::Player;
+
+    fn player(name: &str, skill: u32, is_captain: bool) -> Player {
+        Player {
+            name: name.to_owned(),
+            skill,
+            is_captain,
+            ..Default::default()
+        }
+    }
+
+    fn find<'a>(stats: &'a [PlayerStats], name: &str) -> &'a PlayerStats {
+        stats.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn results_and_company_are_counted() {
+        let ann = player("Ann", 1000, true);
+        let bob = player("Bob", 1000, false);
+        let cat = player("Cat", 1000, true);
+        let dan = player("Dan", 1000, false);
+        let teams = vec![
+            vec![ann.clone(), bob.clone()],
+            vec![cat.clone(), dan.clone()],
+        ];
+        let swapped = vec![
+            vec![ann.clone(), cat.clone()],
+            vec![bob.clone(), dan.clone()],
+        ];
+        let matches = [
+            MatchRecord::new(1, &teams, vec![3, 1]),
+            MatchRecord::new(2, &teams, vec![2, 2]),
+            MatchRecord::new(3, &swapped, vec![0, 1]),
+            // Never finished, so left out
+            MatchRecord::new(4, &teams, vec![1]),
+        ];
+        let stats = player_stats(&matches, RatingModel::Elo);
+
+        let names: Vec<&str> = stats.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Ann", "Bob", "Cat", "Dan"]);
+
+        let ann_stats = find(&stats, "Ann");
+        assert_eq!(
+            (
+                ann_stats.games,
+                ann_stats.wins,
+                ann_stats.draws,
+                ann_stats.losses
+            ),
+            (3, 1, 1, 1)
+        );
+        assert_eq!((ann_stats.captain_games, ann_stats.captain_wins), (3, 1));
+        assert_eq!(ann_stats.win_rate(), Some(0.5));
+        assert_eq!(ann_stats.top_teammates(1), [(bob.id, 2)]);
+        assert_eq!(ann_stats.opponents[&dan.id], 3);
+        assert!(!ann_stats.teammates.contains_key(&ann.id));
+
+        let dan_stats = find(&stats, "Dan");
+        assert_eq!((dan_stats.wins, dan_stats.losses), (1, 1));
+        assert_eq!((dan_stats.captain_games, dan_stats.captain_wins), (0, 0));
+    }
+
+    #[test]
+    fn skill_history_starts_before_the_first_game() {
+        let teams = vec![
+            vec![player("Ann", 1200, false)],
+            vec![player("Bob", 800, false)],
+        ];
+        let matches = [
+            MatchRecord::new(5, &teams, vec![1, 0]),
+            MatchRecord::new(7, &teams, vec![1, 0]),
+        ];
+        let stats = player_stats(&matches, RatingModel::Elo);
+        let ann = find(&stats, "Ann");
+
+        let games: Vec<u32> = ann.skill_history.iter().map(|&(game, _)| game).collect();
+        assert_eq!(games, [5, 5, 7]);
+        assert_eq!(ann.skill_history[0].1, 1200);
+        assert!(ann.skill() > 1200);
+        assert!(find(&stats, "Bob").skill() < 800);
+    }
+
+    #[test]
+    fn no_games_no_win_rate() {
+        assert_eq!(PlayerStats::new(Ulid::new()).win_rate(), None);
+        assert!(player_stats(&[], RatingModel::Elo).is_empty());
+    }
+}
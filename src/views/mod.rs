@@ -1,12 +1,34 @@
 use crate::app::SettingsData;
+use ::team_creator::rating::{MatchRecord, RatingModel};
+use ::team_creator::share::Shared;
 
+mod qr_code;
 mod settings;
 mod spin_wheel;
+mod stats;
 mod team_creator;
 
 pub trait View {
     fn name(&self) -> &str;
     fn ui(&mut self, ui: &mut eframe::egui::Ui, settings: &mut SettingsData);
+    /// Takes in what a share link carries. Returns `false` if it isn't for this view.
+    fn import(&mut self, _shared: &Shared) -> bool {
+        false
+    }
+    /// Games recorded in this view, for the views that show them.
+    fn match_log(&self) -> Option<MatchLog<'_>> {
+        None
+    }
+    /// Called whenever the view is switched to, with the games recorded in the other views.
+    fn opened(&mut self, _match_log: Option<MatchLog<'_>>) {}
+}
+
+/// The games recorded so far and how players are rated from them.
+pub struct MatchLog<'a> {
+    pub matches: &'a [MatchRecord],
+    pub model: RatingModel,
+    /// Whether the roster's skills are all left at zero, and shouldn't be shown.
+    pub hide_skills: bool,
 }
 
 pub struct Views {
@@ -21,6 +43,7 @@ impl Default for Views {
             views: vec![
                 Box::<team_creator::TeamCreator>::default(),
                 Box::<spin_wheel::SpinWheel>::default(),
+                Box::<stats::Stats>::default(),
                 Box::<settings::Settings>::default(),
             ],
         }
@@ -37,9 +60,24 @@ impl Views {
         self.views[self.active_view].ui(ui, settings);
     }
 
+    /// Hands what a share link carries to the view it is for, and switches to that view.
+    pub fn import(&mut self, shared: &Shared) {
+        if let Some(index) = self.views.iter_mut().position(|view| view.import(shared)) {
+            self.set_active_view(index);
+        }
+    }
+
     pub fn set_active_view(&mut self, index: usize) {
-        if index < self.views.len() {
+        if index < self.views.len() && index != self.active_view {
             self.active_view = index;
+
+            let (before, rest) = self.views.split_at_mut(index);
+            let (view, after) = rest.split_first_mut().expect("The index is in bounds");
+            let match_log = before
+                .iter()
+                .chain(after.iter())
+                .find_map(|other| other.match_log());
+            view.opened(match_log);
         }
     }
 }
@@ -0,0 +1,139 @@
+use crate::download;
+use eframe::egui;
+use eframe::egui::{Id, Modal, TextureHandle, TextureOptions};
+use team_creator::render;
+
+/// Size of the QR code on screen.
+const SHOWN_SIZE: f32 = 300.0;
+/// Pixels to a module in the saved PNG.
+const PNG_MODULE_SIZE: u32 = 8;
+
+/// Something that can be put in the QR code, like the teams or a share link.
+pub struct QrContent {
+    pub label: String,
+    pub text: String,
+}
+
+impl QrContent {
+    pub fn new(label: &str, text: String) -> Self {
+        QrContent {
+            label: label.to_owned(),
+            text,
+        }
+    }
+}
+
+/// A QR code to scan with a phone, in a dialog where it can be saved as PNG.
+#[derive(Default)]
+pub struct QrDialog {
+    /// What the code can be made of. The dialog is closed while this is empty.
+    contents: Vec<QrContent>,
+    selected: usize,
+    /// The code of the selected content, or why it couldn't be made.
+    texture: Option<Result<TextureHandle, String>>,
+    /// Outcome of the last save.
+    status: Option<Result<String, String>>,
+}
+
+impl QrDialog {
+    /// Opens the dialog with the code of the first of `contents`, letting the user switch to
+    /// the others.
+    pub fn open(&mut self, contents: Vec<QrContent>) {
+        *self = QrDialog {
+            contents,
+            ..Default::default()
+        };
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if self.contents.is_empty() {
+            return;
+        }
+
+        let modal = Modal::new(Id::new("QR Code Modal")).show(ctx, |ui| {
+            ui.set_width(SHOWN_SIZE + 20.0);
+            ui.heading("QR code");
+            ui.separator();
+
+            if self.contents.len() > 1 {
+                ui.horizontal(|ui| {
+                    for (idx, content) in self.contents.iter().enumerate() {
+                        if ui
+                            .radio_value(&mut self.selected, idx, &content.label)
+                            .changed()
+                        {
+                            self.texture = None;
+                            self.status = None;
+                        }
+                    }
+                });
+            }
+
+            let text = &self.contents[self.selected].text;
+            let texture = self.texture.get_or_insert_with(|| {
+                render::qr_code(text, 1)
+                    .map(|image| {
+                        let size = [image.width() as usize, image.height() as usize];
+                        ctx.load_texture(
+                            "qr_code",
+                            egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()),
+                            TextureOptions::NEAREST,
+                        )
+                    })
+                    .map_err(|error| format!("No QR code can be made of this: {}", error))
+            });
+
+            match texture {
+                Ok(texture) => {
+                    ui.vertical_centered(|ui| {
+                        ui.image((texture.id(), egui::vec2(SHOWN_SIZE, SHOWN_SIZE)));
+                    });
+                }
+                Err(message) => {
+                    ui.colored_label(egui::Color32::RED, message);
+                }
+            }
+            let can_save = texture.is_ok();
+
+            match &self.status {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::RED, message);
+                }
+                None => {}
+            }
+
+            ui.separator();
+            egui::Sides::new().show(
+                ui,
+                |ui| {
+                    if ui
+                        .add_enabled(can_save, egui::Button::new("💾 Save PNG"))
+                        .clicked()
+                    {
+                        self.status = Some(save_png(text));
+                    }
+                },
+                |ui| {
+                    if ui.button("❌ Close").clicked() {
+                        ui.close();
+                    }
+                },
+            );
+        });
+
+        if modal.should_close() {
+            *self = QrDialog::default();
+        }
+    }
+}
+
+/// Saves the QR code of `text` as `qr_code.png`, large enough to print.
+fn save_png(text: &str) -> Result<String, String> {
+    let image = render::qr_code(text, PNG_MODULE_SIZE).map_err(|error| error.to_string())?;
+    let png = render::encode_png(&image)
+        .map_err(|error| format!("Failed to draw the QR code: {}", error))?;
+    download::save_file("qr_code.png", &png, "image/png")
+}
@@ -12,6 +12,7 @@ impl PersistentCache for PersistentData {
     }
 }
 
+#[derive(Default)]
 pub struct Settings {}
 
 impl super::View for Settings {
@@ -49,12 +50,6 @@ impl super::View for Settings {
     }
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Settings {}
-    }
-}
-
 impl Settings {
     fn theme_picker(&mut self, ui: &mut egui::Ui, settings: &mut crate::app::SettingsData) {
         if ui
@@ -3,10 +3,14 @@ mod wheel;
 
 use crate::cache::PersistentCache;
 use crate::extensions::PressedEnterExt;
-use crate::views::spin_wheel::wheel::{Choice, Wheel};
+use crate::views::qr_code::{QrContent, QrDialog};
+use crate::views::spin_wheel::wheel::Wheel;
 use eframe::egui;
 use eframe::egui::{Color32, FontId, Id, Modal, Pos2, Stroke};
 use eframe::epaint::PathShape;
+use team_creator::seed::Seed;
+use team_creator::share::Shared;
+use team_creator::wheel::Choice;
 
 #[derive(serde::Serialize, serde::Deserialize)]
 struct PersistentData {
@@ -18,6 +22,11 @@ pub struct SpinWheel {
     pd: PersistentData,
     wheel: Wheel,
     input_text: String,
+    /// Seed typed in by the user to reproduce a spin, random when empty.
+    seed_input: String,
+    /// Seed of the last spin.
+    seed: Option<Seed>,
+    qr_dialog: QrDialog,
 }
 
 impl PersistentCache for PersistentData {
@@ -35,6 +44,9 @@ impl Default for SpinWheel {
             }),
             wheel: Wheel::new(),
             input_text: String::new(),
+            seed_input: String::new(),
+            seed: None,
+            qr_dialog: QrDialog::default(),
         }
     }
 }
@@ -44,6 +56,16 @@ impl super::View for SpinWheel {
         "🎲 Spin Wheel"
     }
 
+    fn import(&mut self, shared: &Shared) -> bool {
+        match shared {
+            Shared::Wheel { choices } => {
+                self.import_choices(choices);
+                true
+            }
+            Shared::Roster { .. } => false,
+        }
+    }
+
     fn ui(&mut self, ui: &mut egui::Ui, _settings: &mut crate::app::SettingsData) {
         if let Some(winner) = self.wheel.winner.clone() {
             let modal = Modal::new(Id::new("Result Modal")).show(ui.ctx(), |ui| {
@@ -51,6 +73,12 @@ impl super::View for SpinWheel {
                 ui.heading("Result:");
                 ui.separator();
                 ui.heading(egui::RichText::new(&winner.label).size(24.0));
+                if let Some(seed) = self.seed {
+                    ui.label(egui::RichText::new(format!("Seed: {}", seed)).weak())
+                        .on_hover_text(
+                            "Spin with this seed and the same entries to get this result again",
+                        );
+                }
                 ui.separator();
                 egui::Sides::new().show(
                     ui,
@@ -73,6 +101,8 @@ impl super::View for SpinWheel {
             }
         }
 
+        self.qr_dialog.show(ui.ctx());
+
         let available_rect = ui.max_rect();
         let painter = ui.painter();
 
@@ -177,7 +207,7 @@ impl super::View for SpinWheel {
                                                 let drag_value = ui.add(
                                                     egui::DragValue::new(&mut real_choice.weight)
                                                         .speed(0.05)
-                                                        .range(1..=75),
+                                                        .range(constants::WEIGHT_RANGE),
                                                 );
 
                                                 if drag_value.changed() {
@@ -212,9 +242,24 @@ impl super::View for SpinWheel {
                             });
                         });
 
+                    let seed = Seed::from_input(&self.seed_input);
+                    ui.horizontal(|ui| {
+                        ui.label("Seed:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.seed_input)
+                                .hint_text("random")
+                                .desired_width(70.0),
+                        );
+                        if seed.is_err() {
+                            ui.colored_label(Color32::RED, "Invalid seed");
+                        }
+                    });
+
                     if ui
                         .add_enabled(
-                            !self.wheel.spinning && !self.pd.wheel_choices.is_empty(),
+                            !self.wheel.spinning
+                                && !self.pd.wheel_choices.is_empty()
+                                && seed.is_ok(),
                             egui::Button::new(
                                 egui::RichText::new("💫 Spin the wheel!")
                                     .font(FontId::proportional(constants::TITLE_SIZE)),
@@ -223,7 +268,10 @@ impl super::View for SpinWheel {
                         .clicked()
                     {
                         self.pd.save_to_disk();
-                        self.wheel.start_spin();
+                        if let Ok(seed) = seed {
+                            self.seed = Some(seed);
+                            self.wheel.start_spin(&mut seed.rng());
+                        }
                     }
 
                     if ui.button("🗑 Clear").clicked() {
@@ -232,12 +280,93 @@ impl super::View for SpinWheel {
                         self.wheel.clear();
                         self.pd.save_to_disk();
                     }
+
+                    #[cfg(target_arch = "wasm32")]
+                    if ui
+                        .add_enabled(
+                            !self.pd.wheel_choices.is_empty(),
+                            egui::Button::new("🔗 Share link"),
+                        )
+                        .on_hover_text(
+                            "Copy a link that opens this wheel in someone else's browser",
+                        )
+                        .clicked()
+                        && let Some(link) = self.share_link()
+                    {
+                        ui.ctx().copy_text(link);
+                    }
+
+                    if ui
+                        .add_enabled(
+                            !self.pd.wheel_choices.is_empty(),
+                            egui::Button::new("📱 QR code"),
+                        )
+                        .clicked()
+                    {
+                        self.open_qr_code();
+                    }
                 });
             });
     }
 }
 
 impl SpinWheel {
+    /// A link that opens this wheel in someone else's browser.
+    #[cfg(target_arch = "wasm32")]
+    fn share_link(&self) -> Option<String> {
+        crate::web::share_link(&Shared::Wheel {
+            choices: self.pd.wheel_choices.clone(),
+        })
+    }
+
+    /// Opens the QR code of the entries, or on the web of a link to the wheel.
+    fn open_qr_code(&mut self) {
+        let labels: Vec<&str> = self
+            .pd
+            .wheel_choices
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect();
+        let entries = QrContent::new("Entries", labels.join("\n"));
+        #[cfg(not(target_arch = "wasm32"))]
+        let link: Option<QrContent> = None;
+        #[cfg(target_arch = "wasm32")]
+        let link = self
+            .share_link()
+            .map(|link| QrContent::new("Link to this wheel", link));
+        self.qr_dialog
+            .open(std::iter::once(entries).chain(link).collect());
+    }
+
+    /// Adds shared entries to the wheel, leaving out the ones it already has.
+    fn import_choices(&mut self, choices: &[Choice]) {
+        for choice in choices {
+            if self.choices_full() {
+                break;
+            }
+            let label = choice.label.trim();
+            if label.is_empty()
+                || self
+                    .pd
+                    .wheel_choices
+                    .iter()
+                    .any(|c| c.label.eq_ignore_ascii_case(label))
+            {
+                continue;
+            }
+            let weight = choice.weight.clamp(
+                *constants::WEIGHT_RANGE.start(),
+                *constants::WEIGHT_RANGE.end(),
+            );
+            self.pd.wheel_choices.push(Choice::new(
+                label.chars().take(constants::MAX_INPUT_SIZE).collect(),
+                Some(weight),
+            ));
+        }
+        self.wheel.reset_rotation(&self.pd.wheel_choices);
+        self.pd.save_to_disk();
+    }
+
     /// Adds a new entry to the wheel choices
     fn add_entry(&mut self, weight: Option<u32>) {
         if self.can_add_entry() {
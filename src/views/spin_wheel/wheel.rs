@@ -5,46 +5,27 @@ use eframe::{
 };
 use egui::{Align2, epaint::TextShape};
 use rand::Rng;
-use ulid::Ulid;
 use std::f32::consts::PI;
+use team_creator::wheel::{self, Choice, Spin};
 
 pub struct Wheel {
     pub radius: f32,
     pub center: Pos2,
     pub spinning: bool,
     pub winner: Option<Choice>,
-    rotation: f32,
-    spin_velocity: f32,
+    spin: Spin,
     pub selected_winner_once: bool,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Choice {
-    pub id: Ulid,
-    pub label: String,
-    pub weight: u32,
-}
-
-impl Choice {
-    pub fn new(label: String, weight: Option<u32>) -> Self {
-        Self {
-            label: label.to_string(),
-            weight: weight.unwrap_or(1),
-            id: Ulid::new(),
-        }
-    }
-}
-
 impl Wheel {
     pub fn new() -> Self {
         Self {
             center: Pos2::default(),
             radius: 0.0,
-            rotation: 0.0,
+            spin: Spin::default(),
             winner: None,
             selected_winner_once: true,
             spinning: false,
-            spin_velocity: 0.0,
         }
     }
 
@@ -52,36 +33,31 @@ impl Wheel {
         *self = Self::new();
     }
 
-    pub fn do_spin(&mut self, ctx: &Context, wheel_choices: &mut Vec<Choice>) {
-        self.rotation += self.spin_velocity;
-        self.spin_velocity *= constants::BREAKING_PERCENT;
-
-        if self.spin_velocity.abs() < constants::MIN_SPEED {
-            self.spin_velocity = 0.0;
+    pub fn do_spin(&mut self, ctx: &Context, wheel_choices: &mut [Choice]) {
+        if !self.spin.step() {
             self.spinning = false;
 
-            if let Some(choice) = self.get_winner(wheel_choices) {
-                if !self.selected_winner_once {
-                    self.winner = Some(choice);
-                    self.selected_winner_once = true;
-                }
+            if let Some(choice) = self.get_winner(wheel_choices)
+                && !self.selected_winner_once
+            {
+                self.winner = Some(choice);
+                self.selected_winner_once = true;
             }
         }
 
         ctx.request_repaint();
     }
 
-    pub fn start_spin(&mut self) {
+    /// Starts spinning from a random angle and speed, see [`Spin`].
+    pub fn start_spin(&mut self, rng: &mut impl Rng) {
         if !self.spinning {
             self.selected_winner_once = false;
-            self.rotation = rand::rng().random_range(0.0..(2.0 * PI));
-            self.spin_velocity = rand::rng()
-                .random_range(constants::SPIN_VELOCITY_MIN..constants::SPIN_VELOCITY_MAX);
+            self.spin = Spin::start(rng);
             self.spinning = true;
         }
     }
 
-    pub fn draw(&mut self, painter: &Painter, wheel_choices: &mut Vec<Choice>) {
+    pub fn draw(&mut self, painter: &Painter, wheel_choices: &mut [Choice]) {
         let colors = [
             Color32::from_rgb(51, 105, 232),
             Color32::from_rgb(213, 15, 37),
@@ -100,9 +76,9 @@ impl Wheel {
             return;
         }
 
-        let total_weight = Wheel::get_total_weight(wheel_choices);
+        let total_weight = wheel::total_weight(wheel_choices);
         let angle_step = 2.0 * PI / total_weight as f32;
-        let mut last_angle = self.rotation;
+        let mut last_angle = self.spin.rotation;
         let choices_len = wheel_choices.len();
 
         for (i, choice) in wheel_choices.iter_mut().enumerate() {
@@ -117,7 +93,7 @@ impl Wheel {
                 0
             }) % colors.len()];
 
-            let actual_steps = (constants::STEPS as u32 * choice.weight / total_weight) as u8;
+            let actual_steps = (constants::STEPS * choice.weight / total_weight) as u8;
             let points: Vec<Pos2> = (0..=actual_steps)
                 .map(|j| {
                     let t = j as f32 / actual_steps as f32;
@@ -153,33 +129,16 @@ impl Wheel {
         egui::pos2(self.center.x + self.radius, self.center.y)
     }
 
-    pub fn reset_rotation(&mut self, choices: &Vec<Choice>) {
-        self.rotation = PI / choices.len() as f32
+    pub fn reset_rotation(&mut self, choices: &[Choice]) {
+        self.spin.rotation = PI / choices.len() as f32
     }
 
-    fn get_winner(&self, wheel_choices: &Vec<Choice>) -> Option<Choice> {
+    fn get_winner(&self, wheel_choices: &[Choice]) -> Option<Choice> {
         if self.spinning {
             return None;
         }
 
-        let angle_step = 2.0 * PI / Wheel::get_total_weight(wheel_choices) as f32;
-        let mut last_angle = self.rotation;
-        let mut minimum: Option<(Choice, f32)> = None;
-
-        for choice in wheel_choices {
-            let actual_end_angle = (last_angle + angle_step * choice.weight as f32) % (2.0 * PI);
-
-            if minimum
-                .as_ref()
-                .map_or(true, |&(_, min_angle)| actual_end_angle < min_angle)
-            {
-                minimum = Some((choice.clone(), actual_end_angle));
-            }
-
-            last_angle += angle_step * choice.weight as f32;
-        }
-
-        minimum.map(|(choice, _)| choice)
+        wheel::winner(self.spin.rotation, wheel_choices).cloned()
     }
 
     fn create_text_shape(
@@ -236,8 +195,4 @@ impl Wheel {
             ..TextShape::new(centered_point, galley, Color32::WHITE)
         }
     }
-
-    fn get_total_weight(choices: &Vec<Choice>) -> u32 {
-        choices.iter().map(|choice| choice.weight).sum()
-    }
 }
@@ -0,0 +1,283 @@
+mod plot;
+
+use crate::cache::PersistentCache;
+use crate::extensions::StringExt;
+use eframe::egui;
+use eframe::egui::RichText;
+use std::collections::HashMap;
+use team_creator::stats::{PlayerStats, player_stats};
+use ulid::Ulid;
+
+/// How many teammates and opponents are listed for the selected player.
+const TOP_PLAYERS: usize = 5;
+
+/// Column the player table is sorted by.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum SortBy {
+    Name,
+    #[default]
+    Games,
+    WinRate,
+    Skill,
+    Captain,
+}
+
+impl SortBy {
+    const ALL: [SortBy; 5] = [
+        SortBy::Name,
+        SortBy::Games,
+        SortBy::WinRate,
+        SortBy::Skill,
+        SortBy::Captain,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SortBy::Name => "Player",
+            SortBy::Games => "Games",
+            SortBy::WinRate => "Win rate",
+            SortBy::Skill => "Skill",
+            SortBy::Captain => "Captain",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PersistentData {
+    sort_by: SortBy,
+    descending: bool,
+}
+
+impl PersistentCache for PersistentData {
+    fn filename() -> &'static str {
+        "stats_cache.ron"
+    }
+}
+
+pub struct Stats {
+    persistent_data: PersistentData,
+    players: Vec<PlayerStats>,
+    /// Number of recorded games the stats were gathered from.
+    games: usize,
+    selected: Option<Ulid>,
+    /// Whether the roster's skills are all zero, so there is no skill to show.
+    hide_skills: bool,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats {
+            persistent_data: PersistentData::read_or(PersistentData {
+                sort_by: SortBy::default(),
+                descending: true,
+            }),
+            players: Vec::new(),
+            games: 0,
+            selected: None,
+            hide_skills: false,
+        }
+    }
+}
+
+impl super::View for Stats {
+    fn name(&self) -> &str {
+        "📊 Stats"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _settings: &mut crate::app::SettingsData) {
+        ui.horizontal(|ui| {
+            ui.heading("Player Stats");
+            ui.label(format!("({} games recorded)", self.games));
+        });
+        ui.separator();
+
+        if self.players.is_empty() {
+            ui.label(
+                RichText::new(
+                    "No results recorded yet. Record who won on the Team Creator's Results tab.",
+                )
+                .weak(),
+            );
+            return;
+        }
+
+        self.show_table(ui);
+
+        let selected = self
+            .selected
+            .and_then(|id| self.players.iter().find(|p| p.id == id));
+        if let Some(player) = selected {
+            ui.separator();
+            self.show_player(ui, player);
+        }
+    }
+
+    /// Gathers the stats again, as games may have been recorded since the view was last shown.
+    fn opened(&mut self, match_log: Option<super::MatchLog<'_>>) {
+        let Some(match_log) = match_log else {
+            return;
+        };
+        self.players = player_stats(match_log.matches, match_log.model);
+        self.games = match_log.matches.len();
+        self.hide_skills = match_log.hide_skills;
+        self.sort();
+    }
+}
+
+impl Stats {
+    /// Columns of the player table, without the skill when it is hidden.
+    fn columns(&self) -> impl Iterator<Item = SortBy> {
+        let hide_skills = self.hide_skills;
+        SortBy::ALL
+            .into_iter()
+            .filter(move |&column| !(hide_skills && column == SortBy::Skill))
+    }
+
+    /// Column the table is sorted by, games when it was the hidden skill.
+    fn sort_by(&self) -> SortBy {
+        match self.persistent_data.sort_by {
+            SortBy::Skill if self.hide_skills => SortBy::Games,
+            sort_by => sort_by,
+        }
+    }
+
+    fn sort(&mut self) {
+        let sort_by = self.sort_by();
+        self.players.sort_by(|a, b| {
+            let ordering = match sort_by {
+                SortBy::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortBy::Games => a.games.cmp(&b.games),
+                SortBy::WinRate => a
+                    .win_rate()
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.win_rate().unwrap_or(0.0)),
+                SortBy::Skill => a.skill().cmp(&b.skill()),
+                SortBy::Captain => a
+                    .captain_wins
+                    .cmp(&b.captain_wins)
+                    .then(a.captain_games.cmp(&b.captain_games)),
+            };
+            if self.persistent_data.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    fn show_table(&mut self, ui: &mut egui::Ui) {
+        let mut sort_by = None;
+        let mut selected = None;
+
+        egui::Grid::new("player_stats")
+            .striped(true)
+            .num_columns(self.columns().count() + 1)
+            .show(ui, |ui| {
+                for column in self.columns() {
+                    let mut header = column.label().to_owned();
+                    if column == self.sort_by() {
+                        header += if self.persistent_data.descending {
+                            " ⏷"
+                        } else {
+                            " ⏶"
+                        };
+                    }
+                    if ui
+                        .selectable_label(column == self.sort_by(), header)
+                        .clicked()
+                    {
+                        sort_by = Some(column);
+                    }
+                }
+                ui.label("W-D-L");
+                ui.end_row();
+
+                for player in &self.players {
+                    if ui
+                        .selectable_label(
+                            self.selected == Some(player.id),
+                            player.name.as_str_or("<unnamed>"),
+                        )
+                        .clicked()
+                    {
+                        selected = Some(player.id);
+                    }
+                    ui.label(player.games.to_string());
+                    ui.label(format_win_rate(player.win_rate()));
+                    if !self.hide_skills {
+                        ui.label(player.skill().to_string());
+                    }
+                    ui.label(format!("{}/{}", player.captain_wins, player.captain_games))
+                        .on_hover_text("Games won as captain, out of games as captain");
+                    ui.label(format!(
+                        "{}-{}-{}",
+                        player.wins, player.draws, player.losses
+                    ));
+                    ui.end_row();
+                }
+            });
+
+        if let Some(column) = sort_by {
+            if column == self.sort_by() {
+                self.persistent_data.descending = !self.persistent_data.descending;
+            } else {
+                self.persistent_data.sort_by = column;
+                self.persistent_data.descending = column != SortBy::Name;
+            }
+            self.persistent_data.save_to_disk();
+            self.sort();
+        }
+
+        if let Some(id) = selected {
+            self.selected = if self.selected == Some(id) {
+                None
+            } else {
+                Some(id)
+            };
+        }
+    }
+
+    fn show_player(&self, ui: &mut egui::Ui, player: &PlayerStats) {
+        let names: HashMap<Ulid, &str> = self
+            .players
+            .iter()
+            .map(|p| (p.id, p.name.as_str_or("<unnamed>")))
+            .collect();
+        let list = |top: Vec<(Ulid, u32)>| -> String {
+            let entries: Vec<String> = top
+                .iter()
+                .map(|(id, count)| {
+                    format!(
+                        "{} ({})",
+                        names.get(id).copied().unwrap_or("<unknown>"),
+                        count
+                    )
+                })
+                .collect();
+            if entries.is_empty() {
+                "-".to_owned()
+            } else {
+                entries.join(", ")
+            }
+        };
+
+        ui.heading(player.name.as_str_or("<unnamed>"));
+        ui.label(format!(
+            "Teammates most often: {}",
+            list(player.top_teammates(TOP_PLAYERS))
+        ));
+        ui.label(format!(
+            "Opponents most often: {}",
+            list(player.top_opponents(TOP_PLAYERS))
+        ));
+
+        if !self.hide_skills {
+            ui.label(RichText::new("Skill over time:").strong());
+            plot::skill_plot(ui, &player.skill_history);
+        }
+    }
+}
+
+fn format_win_rate(win_rate: Option<f32>) -> String {
+    win_rate.map_or("-".to_owned(), |rate| format!("{:.0}%", rate * 100.0))
+}
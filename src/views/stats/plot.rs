@@ -0,0 +1,86 @@
+use eframe::egui;
+use eframe::egui::{Align2, FontId, Pos2, Rect, Sense, Stroke, Vec2};
+
+const PLOT_HEIGHT: f32 = 150.0;
+const MAX_PLOT_WIDTH: f32 = 500.0;
+/// Room on the left for the skill labels.
+const LABEL_WIDTH: f32 = 50.0;
+const POINT_RADIUS: f32 = 3.0;
+
+/// Draws a line through the skill after each game, the first game on the left.
+pub fn skill_plot(ui: &mut egui::Ui, history: &[(u32, u32)]) {
+    let width = ui.available_width().min(MAX_PLOT_WIDTH);
+    let (response, painter) = ui.allocate_painter(Vec2::new(width, PLOT_HEIGHT), Sense::hover());
+    let visuals = ui.visuals();
+    let font = FontId::proportional(12.0);
+
+    let plot = Rect::from_min_max(
+        response.rect.min + Vec2::new(LABEL_WIDTH, POINT_RADIUS),
+        response.rect.max - Vec2::new(POINT_RADIUS, POINT_RADIUS),
+    );
+    painter.rect_filled(plot, 2.0, visuals.extreme_bg_color);
+
+    let lowest = history.iter().map(|&(_, skill)| skill).min().unwrap_or(0);
+    let highest = history.iter().map(|&(_, skill)| skill).max().unwrap_or(0);
+    // A flat line is drawn across the middle
+    let range = (highest - lowest).max(1) as f32;
+    let center = if highest == lowest { 0.5 } else { 0.0 };
+
+    for (skill, y) in [(highest, plot.top()), (lowest, plot.bottom())] {
+        painter.text(
+            Pos2::new(plot.left() - 4.0, y),
+            Align2::RIGHT_CENTER,
+            skill.to_string(),
+            font.clone(),
+            visuals.weak_text_color(),
+        );
+    }
+
+    let step = plot.width() / (history.len().max(2) - 1) as f32;
+    let points: Vec<Pos2> = history
+        .iter()
+        .enumerate()
+        .map(|(idx, &(_, skill))| {
+            let height = (skill - lowest) as f32 / range + center;
+            Pos2::new(
+                plot.left() + idx as f32 * step,
+                plot.bottom() - height * plot.height(),
+            )
+        })
+        .collect();
+
+    let color = visuals.selection.stroke.color;
+    painter.line(points.clone(), Stroke::new(2.0, color));
+    for &point in &points {
+        painter.circle_filled(point, POINT_RADIUS, color);
+    }
+
+    // Value of the point closest to the pointer
+    if let Some(pointer) = response.hover_pos()
+        && let Some((idx, &point)) = points.iter().enumerate().min_by(|a, b| {
+            (a.1.x - pointer.x)
+                .abs()
+                .total_cmp(&(b.1.x - pointer.x).abs())
+        })
+    {
+        let (game, skill) = history[idx];
+        let label = if idx == 0 {
+            format!("Before game {}: {}", game, skill)
+        } else {
+            format!("Game {}: {}", game, skill)
+        };
+        painter.circle_stroke(point, POINT_RADIUS * 2.0, Stroke::new(1.0, color));
+        let align = if point.x > plot.center().x {
+            Align2::RIGHT_BOTTOM
+        } else {
+            Align2::LEFT_BOTTOM
+        };
+        painter.text(
+            point - Vec2::new(0.0, POINT_RADIUS * 2.0),
+            align,
+            label,
+            font,
+            visuals.strong_text_color(),
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use super::TeamCreator;
+use crate::cache::PersistentCache;
+use eframe::egui;
+use eframe::egui::RichText;
+use team_creator::import::{find_by_name, merge_players, parse_text};
+
+impl TeamCreator {
+    /// A box to paste a list of players into, like one copied out of Discord.
+    pub(super) fn show_bulk_paste(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("📝 Paste players")
+            .id_salt("bulk_paste")
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.paste_input)
+                        .hint_text("One player per line, like:\nAlice 1200\nBob, 950, captain\n* Carol - 14000")
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY),
+                );
+
+                let (players, errors) = parse_text(&self.paste_input);
+                let roster = &self.persistent_data.players;
+                let mut new_count = 0;
+
+                for player in &players {
+                    let existing = find_by_name(roster, &player.name).map(|idx| &roster[idx]);
+                    let skill = player.skill.map(|s| format!(" ({})", s)).unwrap_or_default();
+                    let captain = if player.is_captain == Some(true) {
+                        " · captain"
+                    } else {
+                        ""
+                    };
+
+                    match existing {
+                        None => {
+                            new_count += 1;
+                            ui.label(format!("➕ {}{}{}", player.name, skill, captain));
+                        }
+                        Some(existing) => match player.skill {
+                            Some(skill) if skill != existing.skill => {
+                                ui.label(format!(
+                                    "✏ {}: {} → {}{}",
+                                    existing.name, existing.skill, skill, captain
+                                ));
+                            }
+                            _ => {
+                                ui.label(
+                                    RichText::new(format!(
+                                        "✔ {} is already on the roster{}",
+                                        existing.name, captain
+                                    ))
+                                    .weak(),
+                                );
+                            }
+                        },
+                    }
+                }
+
+                for error in &errors {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("Line {}: {} in \"{}\"", error.row, error.message, error.cells[0]),
+                    );
+                }
+
+                let updated = players.len() - new_count;
+                if ui
+                    .add_enabled(
+                        !players.is_empty(),
+                        egui::Button::new(format!("📥 Add {} new, update {}", new_count, updated)),
+                    )
+                    .clicked()
+                {
+                    merge_players(&mut self.persistent_data.players, players);
+                    self.paste_input.clear();
+                    self.persistent_data.save_to_disk();
+                }
+            });
+    }
+}
@@ -0,0 +1,213 @@
+use super::TeamCreator;
+use crate::cache::PersistentCache;
+use crate::extensions::StringExt;
+use eframe::egui;
+use eframe::egui::RichText;
+use team_creator::export;
+use team_creator::import::{
+    Column, Table, count_existing, guess_columns, merge_players, read_players,
+};
+
+/// Failed rows listed under the import preview, the rest are only counted.
+const MAX_SHOWN_ERRORS: usize = 10;
+
+/// Text being imported and what its columns were mapped to.
+#[derive(Default)]
+pub(super) struct CsvImport {
+    text: String,
+    columns: Vec<Column>,
+    has_header: bool,
+    /// The text `columns` were guessed for, so they are only guessed again when it changes.
+    guessed_for: String,
+    /// File to load on native, or to save the roster to.
+    #[cfg(not(target_arch = "wasm32"))]
+    path: String,
+    /// Outcome of the last import, load or save.
+    status: Option<Result<String, String>>,
+}
+
+impl TeamCreator {
+    pub(super) fn show_csv(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("📄 CSV import and export")
+            .id_salt("csv_import")
+            .show(ui, |ui| {
+                self.show_csv_import(ui);
+            });
+    }
+
+    fn show_csv_import(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("📋 Copy roster CSV").clicked() {
+                ui.ctx().copy_text(self.roster_csv());
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.separator();
+                ui.label("File:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.csv_import.path)
+                        .hint_text("roster.csv")
+                        .desired_width(150.0),
+                );
+                let path = self.csv_import.path.as_str_or("roster.csv").to_owned();
+                if ui.button("📂 Load").clicked() {
+                    self.csv_import.status = Some(match std::fs::read_to_string(&path) {
+                        Ok(text) => {
+                            self.csv_import.text = text;
+                            Ok(format!("Loaded {}", path))
+                        }
+                        Err(error) => Err(format!("Failed to read {}: {}", path, error)),
+                    });
+                }
+                if ui.button("💾 Save roster").clicked() {
+                    self.csv_import.status = Some(
+                        std::fs::write(&path, self.roster_csv())
+                            .map(|_| format!("Saved the roster to {}", path))
+                            .map_err(|error| format!("Failed to write {}: {}", path, error)),
+                    );
+                }
+            }
+        });
+
+        ui.add(
+            egui::TextEdit::multiline(&mut self.csv_import.text)
+                .hint_text("Paste rows from a spreadsheet or CSV file, one player per row")
+                .desired_rows(4)
+                .desired_width(f32::INFINITY),
+        );
+
+        if let Some(status) = &self.csv_import.status {
+            match status {
+                Ok(message) => ui.label(message),
+                Err(message) => ui.colored_label(egui::Color32::RED, message),
+            };
+        }
+
+        if self.csv_import.text.trim().is_empty() {
+            return;
+        }
+
+        let table = match Table::from_csv(&self.csv_import.text) {
+            Ok(table) => table,
+            Err(error) => {
+                ui.colored_label(egui::Color32::RED, format!("Not valid CSV: {}", error));
+                return;
+            }
+        };
+
+        if self.csv_import.guessed_for != self.csv_import.text {
+            let (columns, has_header) = guess_columns(&table, &self.persistent_data.attributes);
+            self.csv_import.columns = columns;
+            self.csv_import.has_header = has_header;
+            self.csv_import.guessed_for = self.csv_import.text.clone();
+        }
+        self.csv_import
+            .columns
+            .resize(table.width(), Column::Ignore);
+
+        self.show_column_mapping(ui, &table);
+
+        let (players, errors) =
+            read_players(&table, &self.csv_import.columns, self.csv_import.has_header);
+        let existing = count_existing(&self.persistent_data.players, &players);
+
+        if !self.csv_import.columns.contains(&Column::Name) {
+            ui.colored_label(egui::Color32::RED, "Pick the column with the names");
+        } else {
+            ui.label(format!(
+                "{} players ready: {} new, {} already on the roster will be updated.",
+                players.len(),
+                players.len() - existing,
+                existing
+            ));
+        }
+
+        if !errors.is_empty() {
+            ui.colored_label(
+                egui::Color32::RED,
+                format!(
+                    "{} rows could not be read and will be skipped:",
+                    errors.len()
+                ),
+            );
+            for error in errors.iter().take(MAX_SHOWN_ERRORS) {
+                ui.label(format!(
+                    "Row {}: {} ({})",
+                    error.row,
+                    error.message,
+                    error.cells.join(", ")
+                ));
+            }
+            if errors.len() > MAX_SHOWN_ERRORS {
+                ui.label(
+                    RichText::new(format!("… and {} more", errors.len() - MAX_SHOWN_ERRORS)).weak(),
+                );
+            }
+        }
+
+        if ui
+            .add_enabled(!players.is_empty(), egui::Button::new("📥 Import"))
+            .clicked()
+        {
+            let count = players.len();
+            let added = merge_players(&mut self.persistent_data.players, players);
+            self.persistent_data.save_to_disk();
+            self.csv_import.text.clear();
+            self.csv_import.status = Some(Ok(format!(
+                "Imported {} players, {} of them new.",
+                count, added
+            )));
+        }
+    }
+
+    fn show_column_mapping(&mut self, ui: &mut egui::Ui, table: &Table) {
+        let attributes = &self.persistent_data.attributes;
+        let label = |column: Column| match column {
+            Column::Attribute(id) => attributes
+                .iter()
+                .find(|a| a.id == id)
+                .map_or("<removed>", |a| a.name.as_str_or("<unnamed>"))
+                .to_owned(),
+            _ => column.label().to_owned(),
+        };
+
+        let mut choices = vec![
+            Column::Ignore,
+            Column::Name,
+            Column::Skill,
+            Column::Captain,
+            Column::Tags,
+            Column::Notes,
+        ];
+        choices.extend(attributes.iter().map(|a| Column::Attribute(a.id)));
+
+        ui.checkbox(&mut self.csv_import.has_header, "First row is a header");
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Columns:");
+            let first = table.rows.first();
+            for (idx, column) in self.csv_import.columns.iter_mut().enumerate() {
+                let sample = first
+                    .and_then(|row| row.get(idx))
+                    .map_or("", String::as_str);
+                ui.group(|ui| {
+                    ui.label(RichText::new(sample.as_str_or("(empty)")).weak());
+                    egui::ComboBox::from_id_salt(("csv_column", idx))
+                        .selected_text(label(*column))
+                        .show_ui(ui, |ui| {
+                            for &choice in &choices {
+                                ui.selectable_value(column, choice, label(choice));
+                            }
+                        });
+                });
+            }
+        });
+    }
+
+    fn roster_csv(&self) -> String {
+        export::roster_csv(
+            &self.persistent_data.players,
+            &self.persistent_data.attributes,
+        )
+    }
+}
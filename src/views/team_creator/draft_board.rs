@@ -0,0 +1,220 @@
+use super::{Tab, TeamCreator};
+use eframe::egui;
+use eframe::egui::{RichText, Stroke};
+use std::time::Duration;
+use team_creator::balancer::Player;
+use team_creator::draft::{Draft, pick_log};
+use team_creator::seed::Seed;
+
+/// Columns of the grid of players still available.
+const POOL_COLUMNS: usize = 4;
+
+impl TeamCreator {
+    /// Starts a draft between the captains in `playing`. With auto-pick on, it is simulated to the
+    /// end right away and the teams are shown.
+    pub(super) fn start_draft(&mut self, playing: Vec<Player>, seed: Seed) {
+        let (captains, pool): (Vec<Player>, Vec<Player>) =
+            playing.into_iter().partition(|p| p.is_captain);
+        let mut draft = Draft::new(
+            captains,
+            pool,
+            self.persistent_data.draft_order,
+            &mut seed.rng(),
+        );
+
+        let auto_draft = self.persistent_data.auto_draft;
+        if auto_draft {
+            draft.simulate();
+        }
+
+        self.draft = Some(draft);
+        self.pick_timer = None;
+        self.seed = Some(seed);
+        self.split_error = None;
+        self.tab = Tab::Draft;
+
+        if auto_draft {
+            self.finish_draft();
+        }
+    }
+
+    /// Shows the drafted teams on the Results tab, counting them as a new game.
+    fn finish_draft(&mut self) {
+        let Some(draft) = &self.draft else {
+            return;
+        };
+
+        self.teams = draft.teams().to_vec();
+        self.candidates.clear();
+        self.compare_candidates = false;
+        self.pick_timer = None;
+        self.tab = Tab::Results;
+        self.record_new_game();
+    }
+
+    pub(super) fn show_draft(&mut self, ui: &mut egui::Ui) {
+        let hide_skills = self.hide_skills();
+
+        ui.horizontal(|ui| {
+            ui.heading("Draft:");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                self.reset_button(ui);
+            });
+        });
+
+        let remaining = self.tick_pick_timer(ui);
+        let Some(draft) = &mut self.draft else {
+            return;
+        };
+        let current_team = draft.current_team();
+
+        ui.horizontal(|ui| {
+            match current_team {
+                Some(team_idx) => ui.label(
+                    RichText::new(format!(
+                        "Round {}: Team {} ({}) is picking",
+                        draft.round() + 1,
+                        team_idx + 1,
+                        draft.teams()[team_idx][0].pretty_name(true)
+                    ))
+                    .strong(),
+                ),
+                None => ui.label(RichText::new("Draft complete.").strong()),
+            };
+
+            if let Some(remaining) = remaining {
+                let text = format!("⏱ {}s", remaining.ceil());
+                if remaining <= 5.0 {
+                    ui.colored_label(egui::Color32::RED, text);
+                } else {
+                    ui.label(text);
+                }
+            }
+        });
+        ui.separator();
+
+        // Captains as columns, the one picking outlined
+        ui.horizontal_top(|ui| {
+            for (team_idx, team) in draft.teams().iter().enumerate() {
+                let mut frame = egui::Frame::group(ui.style());
+                if current_team == Some(team_idx) {
+                    frame = frame.stroke(Stroke::new(2.0, ui.visuals().selection.stroke.color));
+                }
+
+                frame.show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        ui.heading(
+                            RichText::new(format!("Team {}", team_idx + 1))
+                                .underline()
+                                .strong(),
+                        );
+                        for player in team {
+                            ui.label(player.pretty_name(hide_skills));
+                        }
+                    });
+                });
+            }
+        });
+        ui.separator();
+
+        if !draft.is_finished() {
+            ui.label("Available:");
+            let mut picked = None;
+            egui::Grid::new("draft_pool")
+                .num_columns(POOL_COLUMNS)
+                .show(ui, |ui| {
+                    for (idx, player) in draft.pool().iter().enumerate() {
+                        if ui.button(player.pretty_name(hide_skills)).clicked() {
+                            picked = Some(player.id);
+                        }
+                        if (idx + 1) % POOL_COLUMNS == 0 {
+                            ui.end_row();
+                        }
+                    }
+                });
+            if let Some(id) = picked {
+                draft.pick(id);
+            }
+            ui.separator();
+        }
+
+        let log = pick_log(draft.picks(), hide_skills);
+        ui.label(RichText::new("Pick log:").strong());
+        for line in log.lines() {
+            ui.label(line);
+        }
+
+        let mut back = false;
+        let mut finish = false;
+        ui.horizontal(|ui| {
+            if ui.button("⬅ Back").clicked() {
+                back = true;
+            }
+            if ui
+                .add_enabled(!draft.picks().is_empty(), egui::Button::new("↶ Undo"))
+                .clicked()
+            {
+                draft.undo();
+            }
+
+            if draft.is_finished() {
+                if ui.button("✔ Show teams").clicked() {
+                    finish = true;
+                }
+            } else {
+                if ui
+                    .button("⏩ Auto-pick")
+                    .on_hover_text("Pick the best available player by skill")
+                    .clicked()
+                {
+                    draft.auto_pick();
+                }
+                if ui.button("⏭ Auto-pick rest").clicked() {
+                    draft.simulate();
+                }
+            }
+
+            if ui.button("📋 Copy log").clicked() {
+                ui.ctx().copy_text(log.clone());
+            }
+        });
+
+        if back {
+            self.draft = None;
+            self.pick_timer = None;
+            self.tab = Tab::TeamCreator;
+        } else if finish {
+            self.finish_draft();
+        }
+    }
+
+    /// Seconds left for the current pick, or `None` without a limit. Picks the best available
+    /// player once time is up.
+    fn tick_pick_timer(&mut self, ui: &egui::Ui) -> Option<f64> {
+        let seconds = self.persistent_data.pick_seconds;
+        let draft = self.draft.as_mut()?;
+        if seconds == 0 || draft.is_finished() {
+            self.pick_timer = None;
+            return None;
+        }
+
+        let now = ui.input(|i| i.time);
+        let picks = draft.picks().len();
+        let started = match self.pick_timer {
+            Some((timer_picks, started)) if timer_picks == picks => started,
+            _ => {
+                self.pick_timer = Some((picks, now));
+                now
+            }
+        };
+
+        let remaining = seconds as f64 - (now - started);
+        if remaining <= 0.0 {
+            draft.auto_pick();
+            return None;
+        }
+
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+        Some(remaining)
+    }
+}
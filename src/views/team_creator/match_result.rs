@@ -0,0 +1,101 @@
+use super::TeamCreator;
+use crate::cache::PersistentCache;
+use eframe::egui;
+use eframe::egui::RichText;
+use team_creator::rating::{MatchRecord, Ratings};
+
+impl TeamCreator {
+    /// Buttons to record who won the shown game, or its score.
+    pub(super) fn show_match_result(&mut self, ui: &mut egui::Ui) {
+        let Some(game) = self.current_game else {
+            return;
+        };
+        let team_count = self.teams.len();
+        self.score_input.resize(team_count, 0);
+
+        let recorded = self
+            .persistent_data
+            .matches
+            .iter()
+            .find(|record| record.game == game);
+        let mut scores = None;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Result:");
+            for team_idx in 0..team_count {
+                if ui.button(format!("🏆 Team {}", team_idx + 1)).clicked() {
+                    let mut won = vec![0; team_count];
+                    won[team_idx] = 1;
+                    scores = Some(won);
+                }
+            }
+            if ui.button("🤝 Draw").clicked() {
+                scores = Some(vec![0; team_count]);
+            }
+
+            ui.separator();
+
+            ui.label("Score:");
+            for (team_idx, score) in self.score_input.iter_mut().enumerate() {
+                if team_idx > 0 {
+                    ui.label("-");
+                }
+                ui.add(egui::DragValue::new(score).speed(0.1).range(0..=999));
+            }
+            if ui.button("💾 Save score").clicked() {
+                scores = Some(self.score_input.clone());
+            }
+
+            if let Some(record) = recorded {
+                ui.separator();
+                ui.label(RichText::new(result_text(record)).weak());
+            }
+        });
+
+        if let Some(scores) = scores {
+            self.record_match(MatchRecord::new(game, &self.teams, scores));
+        }
+    }
+
+    /// Stores the result of a game in the match log, replacing an earlier result of the same game,
+    /// and updates the ratings.
+    fn record_match(&mut self, record: MatchRecord) {
+        let matches = &mut self.persistent_data.matches;
+        match matches.iter_mut().find(|other| other.game == record.game) {
+            Some(other) => *other = record,
+            None => matches.push(record),
+        }
+
+        self.update_ratings();
+        self.persistent_data.save_to_disk();
+    }
+
+    /// Replays the match log with the selected rating model.
+    pub(super) fn update_ratings(&mut self) {
+        self.ratings = Ratings::from_matches(
+            self.persistent_data.rating_model,
+            &self.persistent_data.matches,
+        );
+    }
+}
+
+/// Short description of a recorded result, like "Team 2 won 3-1".
+fn result_text(record: &MatchRecord) -> String {
+    let scores: Vec<String> = record.scores.iter().map(u32::to_string).collect();
+    let score = if record.scores.iter().all(|&s| s <= 1) {
+        String::new()
+    } else {
+        format!(" {}", scores.join("-"))
+    };
+
+    if record.is_draw() {
+        format!("✔ Recorded as a draw{}", score)
+    } else {
+        let winners: Vec<String> = record
+            .winners()
+            .iter()
+            .map(|idx| format!("Team {}", idx + 1))
+            .collect();
+        format!("✔ Recorded: {} won{}", winners.join(" and "), score)
+    }
+}
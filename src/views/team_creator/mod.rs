@@ -1,35 +1,192 @@
-mod team_creator;
+mod bulk_paste;
+mod csv_import;
+mod draft_board;
+mod match_result;
+mod rosters;
 
 use crate::cache::PersistentCache;
-use crate::extensions::PressedEnterExt;
+use crate::download;
+use crate::extensions::{PressedEnterExt, StringExt};
+use crate::views::qr_code::{QrContent, QrDialog};
+use csv_import::CsvImport;
 use eframe::egui;
 use eframe::egui::{CursorIcon, RichText};
-use team_creator::{Player, best_balanced_split, sum_skill};
+use rosters::SavedRoster;
+use std::collections::BTreeSet;
+use team_creator::balancer::{
+    Attribute, Constraint, ConstraintKind, MAX_SKILL, Metric, PastGame, Player, Role, Split,
+    SplitError, SplitOptions, assign_roles, best_balanced_split, pick_bench, repeated_pairs,
+    sum_attribute, sum_skill, unfilled_slots,
+};
+use team_creator::draft::{Draft, DraftOrder, pick_log};
+use team_creator::export::{self, ExportFormat, ExportOptions};
+use team_creator::rating::{MatchRecord, RatingModel, Ratings};
+use team_creator::render;
+use team_creator::save::{self, Migration};
+use team_creator::seed::Seed;
+use team_creator::share::Shared;
+use ulid::Ulid;
+
+/// How many alternative splits the Results tab offers.
+const SPLIT_CANDIDATES: usize = 5;
+
+/// How many past games are kept in the history.
+const MAX_HISTORY: usize = 50;
 
 #[derive(PartialEq, serde::Serialize, serde::Deserialize)]
 enum Tab {
     TeamCreator,
+    Draft,
     Results,
 }
 
+/// How teams are formed from the roster.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Mode {
+    /// The balancer splits the roster.
+    #[default]
+    Balance,
+    /// Captains take turns picking their players.
+    Draft,
+}
+
+impl Mode {
+    fn label(&self) -> &'static str {
+        match self {
+            Mode::Balance => "⚖ Balance",
+            Mode::Draft => "🎯 Captains draft",
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 struct PersistentData {
+    /// Name of the roster being used. It owns the fields listed in [`SavedRoster`], the others are
+    /// the same for every roster.
+    #[serde(default = "default_roster_name")]
+    roster_name: String,
+    /// Everyone on the roster, whether they are here this session or not.
     players: Vec<Player>,
+    /// Players who are not here this session. Everyone else is present and gets to play.
+    #[serde(default)]
+    absent: BTreeSet<Ulid>,
     number_of_teams: usize,
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+    /// Explicit number of players per team, empty to split as evenly as possible.
+    #[serde(default)]
+    team_sizes: Vec<usize>,
+    #[serde(default = "default_short_handed_bonus")]
+    short_handed_bonus: u32,
+    /// Players per team when extra players are benched instead of spread over the teams.
+    #[serde(default)]
+    players_per_team: Option<usize>,
+    /// Number of times teams were created, used to rotate who sits out.
+    #[serde(default)]
+    games_created: u32,
+    #[serde(default)]
+    attributes: Vec<Attribute>,
+    #[serde(default)]
+    roles: Vec<Role>,
+    /// Teams of previous games, oldest first.
+    #[serde(default)]
+    history: Vec<PastGame>,
+    /// How strongly recent teammates are kept apart, see [`SplitOptions::fresh_teams_weight`].
+    #[serde(default)]
+    fresh_teams_weight: u32,
+    #[serde(default)]
+    metric: Metric,
+    #[serde(default)]
+    mode: Mode,
+    #[serde(default)]
+    draft_order: DraftOrder,
+    /// Let every captain pick the best available player instead of picking by hand.
+    #[serde(default)]
+    auto_draft: bool,
+    /// Seconds a captain has for each pick before the best available player is picked for them,
+    /// zero for no limit.
+    #[serde(default = "default_pick_seconds")]
+    pick_seconds: u32,
+    /// Results of played games, oldest first.
+    #[serde(default)]
+    matches: Vec<MatchRecord>,
+    #[serde(default)]
+    rating_model: RatingModel,
+    /// Balance with the ratings learned from the match log instead of the typed skill.
+    #[serde(default)]
+    use_learned_ratings: bool,
+    /// Every roster but the one being used.
+    #[serde(default)]
+    other_rosters: Vec<SavedRoster>,
+    /// Format "📋 Copy" writes the teams in.
+    #[serde(default)]
+    export_format: ExportFormat,
+    #[serde(default = "default_export_template")]
+    export_template: String,
+}
+
+fn default_roster_name() -> String {
+    "Default".to_owned()
+}
+
+fn default_export_template() -> String {
+    export::DEFAULT_TEMPLATE.to_owned()
+}
+
+fn default_short_handed_bonus() -> u32 {
+    50
+}
+
+fn default_pick_seconds() -> u32 {
+    30
 }
 
 pub struct TeamCreator {
     tab: Tab,
     teams: Vec<Vec<Player>>,
+    bench: Vec<Player>,
+    candidates: Vec<Split>,
+    selected_candidate: usize,
+    compare_candidates: bool,
     persistent_data: PersistentData,
     player_being_edited: Option<usize>,
     new_player: Player,
+    /// Tags of the player being edited, separated by commas.
+    tags_input: String,
+    new_constraint: Constraint,
+    split_error: Option<SplitError>,
+    /// Game the shown teams are recorded as in the history.
+    current_game: Option<u32>,
+    /// Seed typed in by the user to reproduce a result, random when empty.
+    seed_input: String,
+    /// Seed the shown teams were created with.
+    seed: Option<Seed>,
+    draft: Option<Draft>,
+    /// Number of picks made when the current pick's countdown started, and when it started.
+    pick_timer: Option<(usize, f64)>,
+    /// Ratings learned from `persistent_data.matches`.
+    ratings: Ratings,
+    /// Score of each team, as typed in on the Results tab.
+    score_input: Vec<u32>,
+    /// Name being typed in for the roster being used, while it is renamed.
+    renaming_roster: Option<String>,
+    confirm_roster_delete: bool,
+    csv_import: CsvImport,
+    /// Players typed into the paste box, one per line.
+    paste_input: String,
+    /// Outcome of saving the teams to a file.
+    export_status: Option<Result<String, String>>,
+    qr_dialog: QrDialog,
 }
 
 impl PersistentCache for PersistentData {
     fn filename() -> &'static str {
         "team_creator_cache.ron"
     }
+
+    fn migrations() -> &'static [Migration] {
+        save::team_creator_migrations::<Self>()
+    }
 }
 
 impl super::View for TeamCreator {
@@ -42,34 +199,113 @@ impl super::View for TeamCreator {
             Tab::TeamCreator => {
                 self.show_team_creator(ui);
             }
+            Tab::Draft => {
+                self.show_draft(ui);
+            }
             Tab::Results => {
                 self.show_results(ui);
             }
         }
     }
+
+    fn import(&mut self, shared: &Shared) -> bool {
+        match shared {
+            Shared::Roster {
+                name,
+                players,
+                attributes,
+                roles,
+            } => {
+                self.import_roster(name, players, attributes, roles);
+                true
+            }
+            Shared::Wheel { .. } => false,
+        }
+    }
+
+    fn match_log(&self) -> Option<super::MatchLog<'_>> {
+        Some(super::MatchLog {
+            matches: &self.persistent_data.matches,
+            model: self.persistent_data.rating_model,
+            hide_skills: self.hide_skills(),
+        })
+    }
 }
 
 impl Default for TeamCreator {
     fn default() -> Self {
+        let persistent_data = PersistentData::read_or(PersistentData {
+            roster_name: default_roster_name(),
+            players: Vec::new(),
+            absent: BTreeSet::new(),
+            number_of_teams: 2,
+            constraints: Vec::new(),
+            team_sizes: Vec::new(),
+            short_handed_bonus: default_short_handed_bonus(),
+            players_per_team: None,
+            games_created: 0,
+            attributes: Vec::new(),
+            roles: Vec::new(),
+            history: Vec::new(),
+            fresh_teams_weight: 0,
+            metric: Metric::default(),
+            mode: Mode::default(),
+            draft_order: DraftOrder::default(),
+            auto_draft: false,
+            pick_seconds: default_pick_seconds(),
+            matches: Vec::new(),
+            rating_model: RatingModel::default(),
+            use_learned_ratings: false,
+            other_rosters: Vec::new(),
+            export_format: ExportFormat::default(),
+            export_template: default_export_template(),
+        });
+        let ratings = Ratings::from_matches(persistent_data.rating_model, &persistent_data.matches);
+
         TeamCreator {
             tab: Tab::TeamCreator,
             teams: Vec::new(),
-            persistent_data: PersistentData::read_or(PersistentData {
-                players: Vec::new(),
-                number_of_teams: 2,
-            }),
+            bench: Vec::new(),
+            candidates: Vec::new(),
+            selected_candidate: 0,
+            compare_candidates: false,
+            persistent_data,
             player_being_edited: None,
             new_player: Player::default(),
+            tags_input: String::new(),
+            new_constraint: Constraint::new(ConstraintKind::Together),
+            split_error: None,
+            current_game: None,
+            seed_input: String::new(),
+            seed: None,
+            draft: None,
+            pick_timer: None,
+            ratings,
+            score_input: Vec::new(),
+            renaming_roster: None,
+            confirm_roster_delete: false,
+            csv_import: CsvImport::default(),
+            paste_input: String::new(),
+            export_status: None,
+            qr_dialog: QrDialog::default(),
         }
     }
 }
 
 impl TeamCreator {
     fn show_team_creator(&mut self, ui: &mut egui::Ui) {
+        self.show_rosters(ui);
+        ui.separator();
+
         let hide_skills = self.hide_skills();
 
         ui.horizontal(|ui| {
-            ui.label(format!("Players: {}", self.persistent_data.players.len()));
+            ui.label(format!(
+                "Players: {}/{}",
+                self.present_count(),
+                self.persistent_data.players.len()
+            ))
+            .on_hover_text("Present this session, out of everyone on the roster");
 
             ui.separator();
 
@@ -85,22 +321,50 @@ impl TeamCreator {
                 for player in &mut self.persistent_data.players {
                     player.is_captain = false;
                 }
+
+                if !self.persistent_data.team_sizes.is_empty() {
+                    self.persistent_data.team_sizes = self.even_team_sizes();
+                }
             }
 
-            let players_per_team: f32 = if self.persistent_data.number_of_teams == 0 {
-                0.0
-            } else {
-                self.persistent_data.players.len() as f32
-                    / self.persistent_data.number_of_teams as f32
-            };
+            let sizes = self.even_team_sizes();
+            let smallest = sizes.iter().min().copied().unwrap_or(0);
+            let largest = sizes.iter().max().copied().unwrap_or(0);
+
+            if self.persistent_data.team_sizes.is_empty() {
+                if let Some(players_per_team) = &mut self.persistent_data.players_per_team {
+                    ui.label("Players per team:");
+                    ui.add(
+                        egui::DragValue::new(players_per_team)
+                            .speed(0.1)
+                            .range(1..=50),
+                    );
+                } else if smallest == largest {
+                    ui.label(format!("({} players per team)", smallest));
+                } else {
+                    ui.label(format!("({}-{} players per team)", smallest, largest));
+                }
+
+                let mut bench_extras = self.persistent_data.players_per_team.is_some();
+                if ui.checkbox(&mut bench_extras, "Bench extras").changed() {
+                    self.persistent_data.players_per_team = bench_extras.then_some(smallest.max(1));
+                }
+            }
+
+            let mut custom_sizes = !self.persistent_data.team_sizes.is_empty();
+            if ui.checkbox(&mut custom_sizes, "Custom sizes").changed() {
+                self.persistent_data.team_sizes = if custom_sizes { sizes } else { Vec::new() };
+            }
 
-            if players_per_team.fract() != 0.0 {
+            let slots = self.slot_count();
+            let player_count = self.present_count();
+            if slots > player_count {
                 ui.colored_label(
                     egui::Color32::RED,
-                    format!("({:.2} players per team)", players_per_team),
+                    format!("({} slots for {} players)", slots, player_count),
                 );
-            } else {
-                ui.label(format!("({:.0} players per team)", players_per_team));
+            } else if slots < player_count {
+                ui.label(format!("({} on the bench)", player_count - slots));
             }
 
             ui.separator();
@@ -108,22 +372,28 @@ impl TeamCreator {
             self.reset_button(ui);
         });
 
+        self.show_team_sizes(ui);
+        self.show_attributes(ui);
+        self.show_roles(ui);
+
         ui.separator();
         let number_of_captains = self
             .persistent_data
             .players
             .iter()
-            .filter(|p| p.is_captain)
+            .filter(|p| p.is_captain && !self.persistent_data.absent.contains(&p.id))
             .count()
             + usize::from(self.new_player.is_captain);
 
-        ui.add_enabled_ui(self.player_being_edited == None, |ui| {
+        ui.add_enabled_ui(self.player_being_edited.is_none(), |ui| {
             ui.horizontal(|ui| {
                 ui.label("Name:");
                 let text_box = ui
                     .add(egui::TextEdit::singleline(&mut self.new_player.name).desired_width(80.0));
                 ui.label("Skill Level:");
-                ui.add(egui::DragValue::new(&mut self.new_player.skill).range(0..=35000));
+                ui.add(egui::DragValue::new(&mut self.new_player.skill).range(0..=MAX_SKILL));
+                attribute_inputs(ui, &mut self.new_player, &self.persistent_data.attributes);
+                role_toggles(ui, &mut self.new_player, &self.persistent_data.roles);
 
                 if number_of_captains < self.persistent_data.number_of_teams
                     || self.new_player.is_captain
@@ -142,7 +412,22 @@ impl TeamCreator {
 
         ui.separator();
 
+        if !self.persistent_data.players.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label("Present:");
+                if ui.button("☑ Everyone").clicked() {
+                    self.persistent_data.absent.clear();
+                }
+                if ui.button("☐ Nobody").clicked() {
+                    self.persistent_data.absent =
+                        self.persistent_data.players.iter().map(|p| p.id).collect();
+                }
+            });
+        }
+
         let mut to_remove = Vec::new();
+        let attributes = self.persistent_data.attributes.clone();
+        let roles = self.persistent_data.roles.clone();
         for (idx, player) in self.persistent_data.players.iter_mut().enumerate() {
             ui.horizontal(|ui| {
                 if self.player_being_edited == Some(idx) {
@@ -150,7 +435,9 @@ impl TeamCreator {
                     let text_box =
                         ui.add(egui::TextEdit::singleline(&mut player.name).desired_width(80.0));
                     ui.label("Skill Level:");
-                    ui.add(egui::DragValue::new(&mut player.skill).range(0..=35000));
+                    ui.add(egui::DragValue::new(&mut player.skill).range(0..=MAX_SKILL));
+                    attribute_inputs(ui, player, &attributes);
+                    role_toggles(ui, player, &roles);
 
                     if number_of_captains < self.persistent_data.number_of_teams
                         || player.is_captain
@@ -159,10 +446,33 @@ impl TeamCreator {
                         ui.checkbox(&mut player.is_captain, "");
                     }
 
+                    ui.label("Tags:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.tags_input)
+                            .hint_text("comma separated")
+                            .desired_width(100.0),
+                    );
+                    ui.label("Notes:");
+                    ui.add(egui::TextEdit::singleline(&mut player.notes).desired_width(120.0));
+
                     if ui.button("💾").clicked() || text_box.pressed_enter(ui.ctx()) {
+                        player.tags = parse_tags(&self.tags_input);
                         self.player_being_edited = None;
                     }
                 } else {
+                    let mut present = !self.persistent_data.absent.contains(&player.id);
+                    if ui
+                        .checkbox(&mut present, "")
+                        .on_hover_text("Here this session")
+                        .changed()
+                    {
+                        if present {
+                            self.persistent_data.absent.remove(&player.id);
+                        } else {
+                            self.persistent_data.absent.insert(player.id);
+                        }
+                    }
+
                     if ui.button("🗑").clicked() {
                         to_remove.push(idx);
                         return;
@@ -170,43 +480,457 @@ impl TeamCreator {
 
                     let prev_button_frame = ui.visuals().button_frame;
                     ui.visuals_mut().button_frame = false;
-                    if ui
-                        .button(player.pretty_name(hide_skills))
-                        .on_hover_cursor(CursorIcon::Text)
-                        .clicked()
-                    {
+                    let mut name = RichText::new(player.pretty_name(hide_skills));
+                    if !present {
+                        name = name.weak();
+                    }
+                    let mut button = ui.button(name).on_hover_cursor(CursorIcon::Text);
+                    if !player.notes.is_empty() {
+                        button = button.on_hover_text(&player.notes);
+                    }
+                    if button.clicked() {
                         self.player_being_edited = Some(idx);
+                        self.tags_input = player.tags.join(", ");
                     };
                     ui.visuals_mut().button_frame = prev_button_frame;
+
+                    if !hide_skills && let Some(rating) = self.ratings.get(player.id) {
+                        ui.label(RichText::new(format!("📈 {}", rating.skill())).weak())
+                            .on_hover_text("Rating learned from recorded results");
+                    }
+
+                    let role_names: Vec<&str> = roles
+                        .iter()
+                        .filter(|r| player.roles.contains(&r.id))
+                        .map(|r| r.name.as_str())
+                        .collect();
+                    if !role_names.is_empty() {
+                        ui.label(RichText::new(role_names.join(", ")).weak());
+                    }
+                    if !player.tags.is_empty() {
+                        ui.label(RichText::new(format!("🏷 {}", player.tags.join(", "))).weak());
+                    }
                 }
             });
         }
 
         for idx in to_remove.into_iter().rev() {
-            self.persistent_data.players.remove(idx);
+            let removed = self.persistent_data.players.remove(idx);
+            self.forget_player(removed.id);
+            self.persistent_data.absent.remove(&removed.id);
         }
 
+        self.show_bulk_paste(ui);
+        self.show_csv(ui);
+        ui.separator();
+        self.show_constraints(ui);
+        ui.separator();
+        self.show_balancing(ui);
+        self.show_history(ui);
+        ui.separator();
+
         ui.horizontal(|ui| {
+            let seed = Seed::from_input(&self.seed_input);
+            let create_label = match self.persistent_data.mode {
+                Mode::Balance => "Create Teams",
+                Mode::Draft => "Start Draft",
+            };
+
             if number_of_captains != 0
                 && (number_of_captains % self.persistent_data.number_of_teams != 0
                     || number_of_captains < self.persistent_data.number_of_teams)
-                || self.persistent_data.players.len() % self.persistent_data.number_of_teams != 0
-                || self.persistent_data.players.len() == 0
+                || self.present_count() < self.persistent_data.number_of_teams
+                || self.slot_count() > self.present_count()
+                || seed.is_err()
+                || (self.persistent_data.mode == Mode::Draft
+                    && number_of_captains != self.persistent_data.number_of_teams)
             {
-                ui.add_enabled(false, egui::Button::new("Create Teams"));
+                ui.add_enabled(false, egui::Button::new(create_label));
             } else {
-                if ui.button("Create Teams").clicked() {
+                if ui.button(create_label).clicked()
+                    && let Ok(seed) = seed
+                {
                     self.persistent_data.save_to_disk();
-                    self.teams = best_balanced_split(
-                        &mut self.persistent_data.players,
-                        self.persistent_data.number_of_teams,
+                    self.create_teams(seed);
+                }
+            }
+
+            ui.label("Seed:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.seed_input)
+                    .hint_text("random")
+                    .desired_width(70.0),
+            )
+            .on_hover_text("Enter the seed of earlier teams to create them again");
+            if seed.is_err() {
+                ui.colored_label(egui::Color32::RED, "Invalid seed");
+            }
+
+            if let Some(error) = &self.split_error {
+                ui.colored_label(egui::Color32::RED, error.to_string());
+            } else if self.persistent_data.mode == Mode::Draft
+                && number_of_captains != self.persistent_data.number_of_teams
+            {
+                ui.label("(a draft needs one captain per team)");
+            }
+        });
+    }
+
+    fn show_team_sizes(&mut self, ui: &mut egui::Ui) {
+        let player_count = self.present_count();
+
+        if !self.persistent_data.team_sizes.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Team sizes:");
+                for size in &mut self.persistent_data.team_sizes {
+                    ui.add(egui::DragValue::new(size).speed(0.1).range(1..=50));
+                }
+            });
+        }
+
+        let uneven = if self.persistent_data.team_sizes.is_empty() {
+            self.persistent_data.players_per_team.is_none()
+                && !player_count.is_multiple_of(self.persistent_data.number_of_teams)
+        } else {
+            let first = self.persistent_data.team_sizes[0];
+            self.persistent_data.team_sizes.iter().any(|&s| s != first)
+        };
+
+        if uneven {
+            ui.horizontal(|ui| {
+                ui.label("Short-handed bonus:");
+                ui.add(
+                    egui::DragValue::new(&mut self.persistent_data.short_handed_bonus)
+                        .range(0..=200)
+                        .suffix("%"),
+                )
+                .on_hover_text(
+                    "Extra skill a smaller team receives for every missing player, \
+                     relative to the average player's skill",
+                );
+            });
+        }
+    }
+
+    fn show_attributes(&mut self, ui: &mut egui::Ui) {
+        let mut to_remove = None;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Attributes:");
+
+            for (idx, attribute) in self.persistent_data.attributes.iter_mut().enumerate() {
+                ui.group(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut attribute.name).desired_width(60.0));
+                    ui.add(
+                        egui::DragValue::new(&mut attribute.weight)
+                            .speed(1.0)
+                            .range(0..=1000)
+                            .suffix("%"),
+                    )
+                    .on_hover_text("How many skill points one point of this attribute is worth");
+                    if ui.button("🗑").clicked() {
+                        to_remove = Some(idx);
+                    }
+                });
+            }
+
+            if ui.button("➕ Attribute").clicked() {
+                let name = format!("Attribute {}", self.persistent_data.attributes.len() + 1);
+                self.persistent_data.attributes.push(Attribute::new(name));
+            }
+        });
+
+        if let Some(idx) = to_remove {
+            let removed = self.persistent_data.attributes.remove(idx);
+            for player in &mut self.persistent_data.players {
+                player.attributes.remove(&removed.id);
+            }
+            self.new_player.attributes.remove(&removed.id);
+        }
+    }
+
+    fn show_roles(&mut self, ui: &mut egui::Ui) {
+        let mut to_remove = None;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Roles:");
+
+            for (idx, role) in self.persistent_data.roles.iter_mut().enumerate() {
+                ui.group(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut role.name).desired_width(60.0));
+                    ui.add(
+                        egui::DragValue::new(&mut role.per_team)
+                            .speed(0.1)
+                            .range(0..=10)
+                            .suffix(" per team"),
+                    );
+                    if ui.button("🗑").clicked() {
+                        to_remove = Some(idx);
+                    }
+                });
+            }
+
+            if ui.button("➕ Role").clicked() {
+                let name = format!("Role {}", self.persistent_data.roles.len() + 1);
+                self.persistent_data.roles.push(Role::new(name));
+            }
+        });
+
+        if let Some(idx) = to_remove {
+            let removed = self.persistent_data.roles.remove(idx);
+            for player in &mut self.persistent_data.players {
+                player.roles.remove(&removed.id);
+            }
+            self.new_player.roles.remove(&removed.id);
+        }
+    }
+
+    /// Team sizes that split the current roster as evenly as possible.
+    fn even_team_sizes(&self) -> Vec<usize> {
+        let players = self.present_count();
+        let teams = self.persistent_data.number_of_teams;
+        (0..teams)
+            .map(|idx| players / teams + usize::from(idx < players % teams))
+            .collect()
+    }
+
+    /// Number of players that get to play, everyone else is benched.
+    fn slot_count(&self) -> usize {
+        if !self.persistent_data.team_sizes.is_empty() {
+            self.persistent_data.team_sizes.iter().sum()
+        } else if let Some(players_per_team) = self.persistent_data.players_per_team {
+            players_per_team * self.persistent_data.number_of_teams
+        } else {
+            self.present_count()
+        }
+    }
+
+    /// Players on the roster who are here this session.
+    fn present_players(&self) -> Vec<Player> {
+        self.persistent_data
+            .players
+            .iter()
+            .filter(|p| !self.persistent_data.absent.contains(&p.id))
+            .cloned()
+            .collect()
+    }
+
+    fn present_count(&self) -> usize {
+        self.persistent_data
+            .players
+            .iter()
+            .filter(|p| !self.persistent_data.absent.contains(&p.id))
+            .count()
+    }
+
+    fn show_constraints(&mut self, ui: &mut egui::Ui) {
+        ui.label("Constraints:");
+
+        let mut to_remove = None;
+        for (idx, constraint) in self.persistent_data.constraints.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.button("🗑").clicked() {
+                    to_remove = Some(idx);
+                }
+
+                let names: Vec<&str> = constraint
+                    .players
+                    .iter()
+                    .filter_map(|id| self.persistent_data.players.iter().find(|p| p.id == *id))
+                    .map(|p| p.name.as_str_or("<unnamed>"))
+                    .collect();
+                ui.label(format!("{}: {}", constraint.kind.label(), names.join(", ")));
+            });
+        }
+
+        if let Some(idx) = to_remove {
+            self.persistent_data.constraints.remove(idx);
+            self.persistent_data.save_to_disk();
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_salt("new_constraint_kind")
+                .selected_text(self.new_constraint.kind.label())
+                .show_ui(ui, |ui| {
+                    for kind in [ConstraintKind::Together, ConstraintKind::Apart] {
+                        ui.selectable_value(&mut self.new_constraint.kind, kind, kind.label());
+                    }
+                });
+
+            for player in &self.persistent_data.players {
+                let selected = self.new_constraint.players.contains(&player.id);
+                if ui
+                    .selectable_label(selected, player.name.as_str_or("<unnamed>"))
+                    .clicked()
+                {
+                    if selected {
+                        self.new_constraint.players.retain(|id| *id != player.id);
+                    } else {
+                        self.new_constraint.players.push(player.id);
+                    }
+                }
+            }
+
+            if ui
+                .add_enabled(
+                    self.new_constraint.players.len() >= 2,
+                    egui::Button::new("➕ Add"),
+                )
+                .clicked()
+            {
+                let kind = self.new_constraint.kind;
+                let constraint = std::mem::replace(&mut self.new_constraint, Constraint::new(kind));
+                self.persistent_data.constraints.push(constraint);
+                self.persistent_data.save_to_disk();
+            }
+        });
+    }
+
+    fn show_balancing(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Mode:");
+            egui::ComboBox::from_id_salt("mode")
+                .selected_text(self.persistent_data.mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in [Mode::Balance, Mode::Draft] {
+                        ui.selectable_value(&mut self.persistent_data.mode, mode, mode.label());
+                    }
+                });
+
+            ui.separator();
+
+            if self.persistent_data.mode == Mode::Draft {
+                ui.label("Pick order:");
+                egui::ComboBox::from_id_salt("draft_order")
+                    .selected_text(self.persistent_data.draft_order.label())
+                    .show_ui(ui, |ui| {
+                        for order in DraftOrder::ALL {
+                            ui.selectable_value(
+                                &mut self.persistent_data.draft_order,
+                                order,
+                                order.label(),
+                            );
+                        }
+                    });
+                ui.checkbox(&mut self.persistent_data.auto_draft, "Auto-pick")
+                    .on_hover_text("Every captain picks the best available player by skill");
+                if !self.persistent_data.auto_draft {
+                    ui.label("Pick timer:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.persistent_data.pick_seconds)
+                            .range(0..=600)
+                            .suffix(" s"),
+                    )
+                    .on_hover_text(
+                        "When it runs out, the best available player is picked. 0 turns it off",
                     );
-                    self.tab = Tab::Results;
                 }
+                return;
+            }
+
+            ui.label("Balance by:");
+            egui::ComboBox::from_id_salt("metric")
+                .selected_text(self.persistent_data.metric.label())
+                .show_ui(ui, |ui| {
+                    for metric in Metric::ALL {
+                        ui.selectable_value(
+                            &mut self.persistent_data.metric,
+                            metric,
+                            metric.label(),
+                        )
+                        .on_hover_text(metric.description());
+                    }
+                })
+                .response
+                .on_hover_text(self.persistent_data.metric.description());
+
+            ui.separator();
+
+            ui.label("Fair skill");
+            ui.add(
+                egui::Slider::new(&mut self.persistent_data.fresh_teams_weight, 0..=1000)
+                    .logarithmic(true),
+            )
+            .on_hover_text(
+                "How much balance to give up to avoid putting players who were teammates \
+                 in recent games together again",
+            );
+            ui.label("Fresh teams");
+
+            ui.separator();
+
+            ui.checkbox(
+                &mut self.persistent_data.use_learned_ratings,
+                "Use learned ratings",
+            )
+            .on_hover_text(
+                "Balance with the ratings learned from recorded results. \
+                 Players without results keep their skill",
+            );
+            ui.label("Rating:");
+            let model = self.persistent_data.rating_model;
+            egui::ComboBox::from_id_salt("rating_model")
+                .selected_text(model.label())
+                .show_ui(ui, |ui| {
+                    for model in RatingModel::ALL {
+                        ui.selectable_value(
+                            &mut self.persistent_data.rating_model,
+                            model,
+                            model.label(),
+                        );
+                    }
+                });
+            if self.persistent_data.rating_model != model {
+                self.update_ratings();
             }
         });
     }
 
+    fn show_history(&mut self, ui: &mut egui::Ui) {
+        let history = &self.persistent_data.history;
+        let mut clear = false;
+
+        egui::CollapsingHeader::new(format!("📜 History ({} games)", history.len()))
+            .id_salt("team_history")
+            .show(ui, |ui| {
+                if history.is_empty() {
+                    ui.label(RichText::new("No teams created yet.").weak());
+                    return;
+                }
+
+                for past in history.iter().rev() {
+                    ui.label(RichText::new(format!("Game {}", past.game)).strong());
+                    for (team_idx, team) in past.teams.iter().enumerate() {
+                        let names: Vec<&str> =
+                            team.iter().map(|p| p.name.as_str_or("<unnamed>")).collect();
+                        ui.label(format!("Team {}: {}", team_idx + 1, names.join(", ")));
+                    }
+                }
+
+                if ui.button("🗑 Clear history").clicked() {
+                    clear = true;
+                }
+            });
+
+        if clear {
+            self.persistent_data.history.clear();
+            self.current_game = None;
+            self.persistent_data.save_to_disk();
+        }
+    }
+
+    /// Drops a removed player from every constraint, along with constraints that no longer
+    /// involve at least two players.
+    fn forget_player(&mut self, id: Ulid) {
+        self.new_constraint.players.retain(|other| *other != id);
+        for constraint in &mut self.persistent_data.constraints {
+            constraint.players.retain(|other| *other != id);
+        }
+        self.persistent_data
+            .constraints
+            .retain(|constraint| constraint.players.len() >= 2);
+    }
+
     fn show_results(&mut self, ui: &mut egui::Ui) {
         let hide_skills = self.hide_skills();
 
@@ -217,7 +941,243 @@ impl TeamCreator {
             });
         });
 
+        if !self.candidates.is_empty() {
+            self.candidate_picker(ui, hide_skills);
+        }
+        if !hide_skills {
+            self.show_compensation(ui);
+        }
+        ui.separator();
+
+        if self.compare_candidates {
+            self.show_candidate_comparison(ui, hide_skills);
+        } else {
+            self.show_teams(ui, hide_skills);
+        }
+
+        ui.separator();
+        self.show_match_result(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button("⬅ Back").clicked() {
+                self.tab = Tab::TeamCreator;
+            }
+            if ui
+                .button("🔄 Recreate")
+                .on_hover_text("Create new teams from everyone present, with a new seed")
+                .clicked()
+            {
+                self.create_teams(Seed::random());
+            }
+            if ui.button("📋 Copy").clicked() {
+                self.copy_teams_to_clipboard(ui);
+            }
+            egui::ComboBox::from_id_salt("export_format")
+                .selected_text(self.persistent_data.export_format.label())
+                .show_ui(ui, |ui| {
+                    for format in ExportFormat::ALL {
+                        ui.selectable_value(
+                            &mut self.persistent_data.export_format,
+                            format,
+                            format.label(),
+                        );
+                    }
+                });
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button("💾 Save").clicked() {
+                let path = format!("teams.{}", self.persistent_data.export_format.extension());
+                self.export_status = Some(
+                    std::fs::write(&path, self.export_teams())
+                        .map(|_| format!("Saved to {}", path))
+                        .map_err(|error| format!("Failed to write {}: {}", path, error)),
+                );
+            }
+            if ui
+                .button("🖼 Save image")
+                .on_hover_text("The teams as a PNG picture, for posting in Discord")
+                .clicked()
+            {
+                self.export_status = Some(self.save_teams_image());
+            }
+            if ui
+                .button("📱 QR code")
+                .on_hover_text("Show the teams as a QR code, to pull them up on a phone")
+                .clicked()
+            {
+                self.open_qr_code();
+            }
+            if let Some(draft) = &self.draft
+                && ui.button("📋 Copy pick log").clicked()
+            {
+                ui.ctx().copy_text(pick_log(draft.picks(), hide_skills));
+            }
+
+            match &self.export_status {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::RED, message);
+                }
+                None => {}
+            }
+        });
+
+        if self.persistent_data.export_format == ExportFormat::Template {
+            self.show_export_template(ui);
+        }
+
+        self.qr_dialog.show(ui.ctx());
+    }
+
+    fn show_export_template(&mut self, ui: &mut egui::Ui) {
+        let placeholders: Vec<String> = export::TEMPLATE_PLACEHOLDERS
+            .iter()
+            .map(|(placeholder, meaning)| format!("{}: {}", placeholder, meaning))
+            .collect();
+
+        ui.horizontal(|ui| {
+            ui.label("Template:");
+            ui.add(
+                egui::TextEdit::multiline(&mut self.persistent_data.export_template)
+                    .desired_rows(2)
+                    .desired_width(300.0),
+            )
+            .on_hover_text(format!(
+                "Written once for every team, with\n{}",
+                placeholders.join("\n")
+            ));
+            if ui
+                .button("↻")
+                .on_hover_text("Back to the default")
+                .clicked()
+            {
+                self.persistent_data.export_template = default_export_template();
+            }
+        });
+    }
+
+    fn candidate_picker(&mut self, ui: &mut egui::Ui, hide_skills: bool) {
+        let count = self.candidates.len();
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!self.compare_candidates, |ui| {
+                if ui
+                    .add_enabled(self.selected_candidate > 0, egui::Button::new("◀"))
+                    .clicked()
+                {
+                    self.select_candidate(self.selected_candidate - 1);
+                }
+                ui.label(format!("Option {}/{}", self.selected_candidate + 1, count));
+                if ui
+                    .add_enabled(self.selected_candidate + 1 < count, egui::Button::new("▶"))
+                    .clicked()
+                {
+                    self.select_candidate(self.selected_candidate + 1);
+                }
+            });
+
+            if !hide_skills && let Some(split) = self.candidates.get(self.selected_candidate) {
+                ui.label(format!("{}: {}", self.persistent_data.metric, split.score))
+                    .on_hover_text(self.persistent_data.metric.description());
+            }
+
+            if let Some(seed) = self.seed {
+                ui.label(format!("Seed: {}", seed)).on_hover_text(
+                    "Create teams with this seed and the same roster to get these teams again",
+                );
+            }
+
+            let repeats = repeated_pairs(&self.teams, &self.earlier_games());
+            if repeats > 0 {
+                ui.label(format!(
+                    "🔁 {} pair{} played together recently",
+                    repeats,
+                    if repeats == 1 { "" } else { "s" }
+                ));
+            }
+
+            ui.separator();
+            ui.toggle_value(&mut self.compare_candidates, "⚖ Compare");
+        });
+    }
+
+    /// Explains which teams were given extra skill for playing short-handed.
+    fn show_compensation(&self, ui: &mut egui::Ui) {
+        let Some(split) = self.candidates.get(self.selected_candidate) else {
+            return;
+        };
+        let largest_team = self.teams.iter().map(Vec::len).max().unwrap_or(0);
+
+        for (team_idx, &handicap) in split.handicaps.iter().enumerate() {
+            let missing = largest_team - self.teams.get(team_idx).map_or(0, Vec::len);
+            if missing == 0 {
+                continue;
+            }
+
+            ui.label(format!(
+                "Team {} is {} player{} short and was given {} extra skill to compensate.",
+                team_idx + 1,
+                missing,
+                if missing == 1 { "" } else { "s" },
+                handicap
+            ));
+        }
+    }
+
+    fn show_candidate_comparison(&mut self, ui: &mut egui::Ui, hide_skills: bool) {
+        let mut selected = None;
+
+        ui.horizontal_wrapped(|ui| {
+            for (candidate_idx, split) in self.candidates.iter().enumerate() {
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new(format!("Option {}", candidate_idx + 1)).strong(),
+                            );
+                            if !hide_skills {
+                                ui.label(format!("({})", split.score));
+                            }
+                        });
+
+                        for (team_idx, team) in split.teams.iter().enumerate() {
+                            ui.label(RichText::new(format!("Team {}", team_idx + 1)).underline());
+                            for player in team {
+                                ui.label(format!(
+                                    "{}{}",
+                                    player.pretty_name(hide_skills),
+                                    role_suffix(team, player, &self.persistent_data.roles)
+                                ));
+                            }
+                        }
+
+                        if ui
+                            .selectable_label(candidate_idx == self.selected_candidate, "✔ Use")
+                            .clicked()
+                        {
+                            selected = Some(candidate_idx);
+                        }
+                    });
+                });
+            }
+        });
+
+        if let Some(candidate_idx) = selected {
+            self.select_candidate(candidate_idx);
+            self.compare_candidates = false;
+        }
+    }
+
+    fn show_teams(&mut self, ui: &mut egui::Ui, hide_skills: bool) {
         let teams_snapshot = self.teams.clone();
+        let mut groups_snapshot = teams_snapshot.clone();
+        groups_snapshot.push(self.bench.clone());
+        let handicaps = self
+            .candidates
+            .get(self.selected_candidate)
+            .map(|split| split.handicaps.clone())
+            .unwrap_or_default();
 
         ui.horizontal(|ui| {
             for (team_idx, team) in teams_snapshot.iter().enumerate() {
@@ -231,96 +1191,300 @@ impl TeamCreator {
 
                         if !hide_skills {
                             ui.label(format!("{}", sum_skill(team)));
+
+                            if let Some(&handicap) = handicaps.get(team_idx)
+                                && handicap > 0
+                            {
+                                ui.label(format!("(+{} handicap)", handicap));
+                            }
                         }
                     });
 
-                    for (player_idx, player) in team.iter().enumerate() {
-                        let prev_button_frame = ui.visuals().button_frame;
-                        ui.visuals_mut().button_frame = false;
-
-                        ui.menu_button(player.pretty_name(hide_skills), |ui| {
-                            for (other_team_idx, other_team) in teams_snapshot.iter().enumerate() {
-                                if other_team_idx == team_idx {
-                                    continue;
-                                }
-
-                                ui.label(format!("Team {}", other_team_idx + 1));
-
-                                for (other_player_idx, other_player) in
-                                    other_team.iter().enumerate()
-                                {
-                                    if other_player.is_captain {
-                                        continue;
-                                    }
-
-                                    if ui
-                                        .selectable_label(
-                                            false,
-                                            other_player.pretty_name(hide_skills),
-                                        )
-                                        .clicked()
-                                    {
-                                        self.swap_players(
-                                            team_idx,
-                                            player_idx,
-                                            other_team_idx,
-                                            other_player_idx,
-                                        );
-                                        ui.close();
-                                        return;
-                                    }
-                                }
-
-                                ui.separator();
-                            }
-                        });
+                    if unfilled_slots(team, &self.persistent_data.roles) > 0 {
+                        ui.colored_label(egui::Color32::RED, "⚠ Missing roles");
+                    }
+
+                    if !self.persistent_data.attributes.is_empty() {
+                        let totals: Vec<String> = self
+                            .persistent_data
+                            .attributes
+                            .iter()
+                            .map(|a| format!("{} {}", a.name, sum_attribute(team, a.id)))
+                            .collect();
+                        ui.label(RichText::new(totals.join(" · ")).weak());
+                    }
 
-                        ui.visuals_mut().button_frame = prev_button_frame;
+                    for (player_idx, player) in team.iter().enumerate() {
+                        self.player_menu(
+                            ui,
+                            hide_skills,
+                            &groups_snapshot,
+                            team_idx,
+                            player_idx,
+                            player,
+                        );
                     }
                 });
 
-                if team_idx < self.teams.len() - 1 {
+                if team_idx < self.teams.len() - 1 || !self.bench.is_empty() {
                     ui.separator();
                 }
             }
+
+            if !self.bench.is_empty() {
+                let bench_idx = self.teams.len();
+                ui.vertical(|ui| {
+                    ui.heading(RichText::new("Bench").underline().strong());
+
+                    for (player_idx, player) in groups_snapshot[bench_idx].iter().enumerate() {
+                        self.player_menu(
+                            ui,
+                            hide_skills,
+                            &groups_snapshot,
+                            bench_idx,
+                            player_idx,
+                            player,
+                        );
+                    }
+                });
+            }
         });
+    }
 
-        ui.horizontal(|ui| {
-            if ui.button("⬅ Back").clicked() {
-                self.tab = Tab::TeamCreator;
+    /// Shows a player of the given group (a team, or the bench after the last team) as a button
+    /// whose menu swaps them with a player of another group.
+    fn player_menu(
+        &mut self,
+        ui: &mut egui::Ui,
+        hide_skills: bool,
+        groups_snapshot: &[Vec<Player>],
+        group_idx: usize,
+        player_idx: usize,
+        player: &Player,
+    ) {
+        let prev_button_frame = ui.visuals().button_frame;
+        ui.visuals_mut().button_frame = false;
+
+        let role = match groups_snapshot.get(group_idx) {
+            Some(team) if group_idx < self.teams.len() => {
+                role_suffix(team, player, &self.persistent_data.roles)
             }
-            if ui.button("🔄 Recreate").clicked() {
-                self.teams = best_balanced_split(
-                    &mut self.persistent_data.players,
-                    self.persistent_data.number_of_teams,
-                );
+            _ => String::new(),
+        };
+
+        ui.menu_button(
+            format!("{}{}", player.pretty_name(hide_skills), role),
+            |ui| {
+                for (other_group_idx, other_group) in groups_snapshot.iter().enumerate() {
+                    if other_group_idx == group_idx
+                        || (player.is_captain && other_group_idx == self.teams.len())
+                    {
+                        continue;
+                    }
+
+                    ui.label(self.group_name(other_group_idx));
+
+                    for (other_player_idx, other_player) in other_group.iter().enumerate() {
+                        if other_player.is_captain {
+                            continue;
+                        }
+
+                        if ui
+                            .selectable_label(false, other_player.pretty_name(hide_skills))
+                            .clicked()
+                        {
+                            self.swap_players(
+                                group_idx,
+                                player_idx,
+                                other_group_idx,
+                                other_player_idx,
+                            );
+                            ui.close();
+                            return;
+                        }
+                    }
+
+                    ui.separator();
+                }
+            },
+        );
+
+        ui.visuals_mut().button_frame = prev_button_frame;
+    }
+
+    fn group_name(&self, group_idx: usize) -> String {
+        if group_idx < self.teams.len() {
+            format!("Team {}", group_idx + 1)
+        } else {
+            "Bench".to_owned()
+        }
+    }
+
+    /// Benches the players that don't fit in the teams, then balances or drafts the rest.
+    fn create_teams(&mut self, seed: Seed) {
+        let present = self.present_players();
+        let bench_size = present.len().saturating_sub(self.slot_count());
+        let (playing, bench) = pick_bench(&present, bench_size, &mut seed.rng());
+
+        self.bench = bench;
+        self.current_game = None;
+
+        match self.persistent_data.mode {
+            Mode::Balance => {
+                self.balance_teams(playing, seed);
+                if self.split_error.is_none() {
+                    self.record_new_game();
+                }
             }
-            if ui.button("📋 Copy").clicked() {
-                self.copy_teams_to_clipboard(ui);
+            Mode::Draft => self.start_draft(playing, seed),
+        }
+    }
+
+    /// Counts the shown teams as a new game, in the history and for who sits out next.
+    fn record_new_game(&mut self) {
+        self.persistent_data.games_created += 1;
+        self.current_game = Some(self.persistent_data.games_created);
+        self.record_game();
+        self.record_bench();
+    }
+
+    /// Stores the shown teams in the history as the current game, replacing what was stored for
+    /// it before.
+    fn record_game(&mut self) {
+        let Some(game) = self.current_game else {
+            return;
+        };
+
+        let past = PastGame::new(game, &self.teams);
+        let history = &mut self.persistent_data.history;
+        match history.last_mut() {
+            Some(last) if last.game == game => *last = past,
+            _ => history.push(past),
+        }
+
+        let excess = history.len().saturating_sub(MAX_HISTORY);
+        history.drain(..excess);
+        self.persistent_data.save_to_disk();
+    }
+
+    /// History without the game currently being shown, which should not count against itself.
+    fn earlier_games(&self) -> Vec<PastGame> {
+        self.persistent_data
+            .history
+            .iter()
+            .filter(|past| Some(past.game) != self.current_game)
+            .cloned()
+            .collect()
+    }
+
+    /// Remembers who sits out this game, so they play first next time.
+    fn record_bench(&mut self) {
+        let game = self.persistent_data.games_created;
+
+        for player in &mut self.persistent_data.players {
+            if self.bench.iter().any(|p| p.id == player.id) {
+                player.last_benched = Some(game);
+            } else if let Some(playing) = self.teams.iter().flatten().find(|p| p.id == player.id) {
+                // Players keep the value they had before this game
+                player.last_benched = playing.last_benched;
             }
-        });
+        }
+
+        self.persistent_data.save_to_disk();
+    }
+
+    /// Runs the balancer on `playing` and shows its most balanced split, or goes back to the Team
+    /// Creator tab with the reason the roster could not be split.
+    fn balance_teams(&mut self, mut playing: Vec<Player>, seed: Seed) {
+        if self.persistent_data.use_learned_ratings {
+            for player in &mut playing {
+                if let Some(rating) = self.ratings.get(player.id) {
+                    player.skill = rating.skill();
+                }
+            }
+        }
+
+        let history = self.earlier_games();
+        let options = SplitOptions {
+            team_count: self.persistent_data.number_of_teams,
+            team_sizes: &self.persistent_data.team_sizes,
+            short_handed_bonus: self.persistent_data.short_handed_bonus,
+            attributes: &self.persistent_data.attributes,
+            roles: &self.persistent_data.roles,
+            constraints: &self.persistent_data.constraints,
+            history: &history,
+            fresh_teams_weight: self.persistent_data.fresh_teams_weight,
+            metric: self.persistent_data.metric,
+            candidates: SPLIT_CANDIDATES,
+        };
+
+        match best_balanced_split(&mut playing, &options, &mut seed.rng()) {
+            Ok(candidates) => {
+                self.candidates = candidates;
+                self.draft = None;
+                self.seed = Some(seed);
+                self.split_error = None;
+                self.compare_candidates = false;
+                self.select_candidate(0);
+                self.tab = Tab::Results;
+            }
+            Err(error) => {
+                self.split_error = Some(error);
+                self.tab = Tab::TeamCreator;
+            }
+        }
+    }
+
+    /// Replaces the shown teams with the given candidate, discarding any manual swaps.
+    fn select_candidate(&mut self, candidate_idx: usize) {
+        if let Some(split) = self.candidates.get(candidate_idx) {
+            self.selected_candidate = candidate_idx;
+            self.teams = split.teams.clone();
+            self.record_game();
+        }
     }
 
-    fn hide_skills(&mut self) -> bool {
+    fn hide_skills(&self) -> bool {
         self.persistent_data.players.iter().all(|p| p.skill == 0)
     }
 
+    /// Starts a new session by clearing the teams. The roster, who is present, captains,
+    /// constraints and team settings are kept, as are past games.
     fn reset_button(&mut self, ui: &mut egui::Ui) {
-        if ui.button("↻ Reset").clicked() {
-            self.persistent_data.players.clear();
+        if ui
+            .button("↻ Reset")
+            .on_hover_text("Clear the teams to start over. The roster and settings are kept")
+            .clicked()
+        {
             self.teams.clear();
-            self.persistent_data.number_of_teams = 2;
+            self.bench.clear();
+            self.candidates.clear();
+            self.draft = None;
+            self.split_error = None;
+            self.current_game = None;
             self.tab = Tab::TeamCreator;
-            self.persistent_data.save_to_disk();
         }
     }
 
     /// Swap two players between different teams safely. If the teams are the same, do nothing.
+    /// The bench can be swapped with as the team after the last one.
     fn swap_players(&mut self, t1: usize, p1: usize, t2: usize, p2: usize) {
         if t1 == t2 {
             return;
         }
 
+        let bench_idx = self.teams.len();
+        self.teams.push(std::mem::take(&mut self.bench));
+        self.swap_within_teams(t1, p1, t2, p2);
+        self.bench = self.teams.pop().unwrap_or_default();
+        self.record_game();
+
+        if t1 == bench_idx || t2 == bench_idx {
+            self.record_bench();
+        }
+    }
+
+    fn swap_within_teams(&mut self, t1: usize, p1: usize, t2: usize, p2: usize) {
         let (team_a, team_b) = if t1 < t2 {
             let (left, right) = self.teams.split_at_mut(t2);
             (&mut left[t1], &mut right[0])
@@ -334,25 +1498,95 @@ impl TeamCreator {
         }
     }
 
-    fn copy_teams_to_clipboard(&mut self, ui: &mut egui::Ui) {
-        let hide = self.hide_skills();
-        let mut output = String::new();
+    /// Opens the QR code of the shown teams, or on the web of a link to the roster.
+    fn open_qr_code(&mut self) {
+        let hide_skills = self.hide_skills();
+        let teams = QrContent::new("Teams", export::text(&self.teams, &self.bench, hide_skills));
+        #[cfg(not(target_arch = "wasm32"))]
+        let link: Option<QrContent> = None;
+        #[cfg(target_arch = "wasm32")]
+        let link = self
+            .persistent_data
+            .share_link()
+            .map(|link| QrContent::new("Link to this roster", link));
+        self.qr_dialog
+            .open(std::iter::once(teams).chain(link).collect());
+    }
 
-        for (team_idx, team) in self.teams.iter().enumerate() {
-            let team_header = if hide {
-                format!("Team {}:\n", team_idx + 1)
-            } else {
-                format!("Team {} ({}):\n", team_idx + 1, sum_skill(team))
-            };
-            output.push_str(&team_header);
+    /// Draws the shown teams and saves them as `teams.png`.
+    fn save_teams_image(&mut self) -> Result<String, String> {
+        let hide_skills = self.hide_skills();
+        let png = render::teams_png(&self.teams, &self.bench, hide_skills)
+            .map_err(|error| format!("Failed to draw the teams: {}", error))?;
+        download::save_file("teams.png", &png, "image/png")
+    }
 
-            for player in team {
-                output.push_str(&format!("- {}\n", player.pretty_name(hide)));
-            }
+    fn copy_teams_to_clipboard(&mut self, ui: &mut egui::Ui) {
+        ui.ctx().copy_text(self.export_teams());
+    }
 
-            output.push('\n');
+    /// The shown teams in the chosen export format.
+    fn export_teams(&mut self) -> String {
+        let hide_skills = self.hide_skills();
+        let options = ExportOptions {
+            attributes: &self.persistent_data.attributes,
+            roles: &self.persistent_data.roles,
+            hide_skills,
+            template: &self.persistent_data.export_template,
+        };
+        export::export(
+            self.persistent_data.export_format,
+            &self.teams,
+            &self.bench,
+            &options,
+        )
+    }
+}
+
+/// Tags typed in as a comma separated list, without blanks and duplicates.
+fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(str::trim) {
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_owned());
         }
+    }
+    tags
+}
+
+/// Inputs for each of the roster's attributes of `player`.
+fn attribute_inputs(ui: &mut egui::Ui, player: &mut Player, attributes: &[Attribute]) {
+    for attribute in attributes {
+        ui.label(format!("{}:", attribute.name.as_str_or("<unnamed>")));
+        ui.add(
+            egui::DragValue::new(player.attributes.entry(attribute.id).or_insert(0))
+                .range(0..=MAX_SKILL),
+        );
+    }
+}
 
-        ui.ctx().copy_text(output);
+/// Toggles for which of the roster's roles `player` can fill.
+fn role_toggles(ui: &mut egui::Ui, player: &mut Player, roles: &[Role]) {
+    for role in roles {
+        let selected = player.roles.contains(&role.id);
+        if ui
+            .selectable_label(selected, role.name.as_str_or("<unnamed>"))
+            .clicked()
+        {
+            if selected {
+                player.roles.remove(&role.id);
+            } else {
+                player.roles.insert(role.id);
+            }
+        }
     }
 }
+
+/// The role `player` fills on `team`, formatted to follow their name.
+fn role_suffix(team: &[Player], player: &Player, roles: &[Role]) -> String {
+    assign_roles(team, roles)
+        .get(&player.id)
+        .and_then(|id| roles.iter().find(|r| r.id == *id))
+        .map(|role| format!(" · {}", role.name))
+        .unwrap_or_default()
+}
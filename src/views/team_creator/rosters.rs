@@ -0,0 +1,326 @@
+use super::{PersistentData, Tab, TeamCreator, default_short_handed_bonus};
+use crate::cache::PersistentCache;
+use crate::extensions::{PressedEnterExt, StringExt};
+use eframe::egui;
+use eframe::egui::{Id, Modal};
+use std::collections::BTreeSet;
+use team_creator::balancer::{Attribute, Constraint, PastGame, Player, Role};
+use team_creator::rating::MatchRecord;
+#[cfg(target_arch = "wasm32")]
+use team_creator::share::Shared;
+use team_creator::share::SharedPlayer;
+use ulid::Ulid;
+
+/// A roster that is not being used, with everything that belongs to it. The roster being used
+/// lives in the fields of [`PersistentData`] it was swapped into.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(super) struct SavedRoster {
+    name: String,
+    players: Vec<Player>,
+    absent: BTreeSet<Ulid>,
+    number_of_teams: usize,
+    constraints: Vec<Constraint>,
+    team_sizes: Vec<usize>,
+    short_handed_bonus: u32,
+    players_per_team: Option<usize>,
+    games_created: u32,
+    attributes: Vec<Attribute>,
+    roles: Vec<Role>,
+    history: Vec<PastGame>,
+    matches: Vec<MatchRecord>,
+}
+
+impl SavedRoster {
+    /// An empty roster for two teams.
+    fn new(name: String) -> Self {
+        SavedRoster {
+            name,
+            players: Vec::new(),
+            absent: BTreeSet::new(),
+            number_of_teams: 2,
+            constraints: Vec::new(),
+            team_sizes: Vec::new(),
+            short_handed_bonus: default_short_handed_bonus(),
+            players_per_team: None,
+            games_created: 0,
+            attributes: Vec::new(),
+            roles: Vec::new(),
+            history: Vec::new(),
+            matches: Vec::new(),
+        }
+    }
+}
+
+impl PersistentData {
+    /// A copy of the roster being used.
+    fn current_roster(&self) -> SavedRoster {
+        SavedRoster {
+            name: self.roster_name.clone(),
+            players: self.players.clone(),
+            absent: self.absent.clone(),
+            number_of_teams: self.number_of_teams,
+            constraints: self.constraints.clone(),
+            team_sizes: self.team_sizes.clone(),
+            short_handed_bonus: self.short_handed_bonus,
+            players_per_team: self.players_per_team,
+            games_created: self.games_created,
+            attributes: self.attributes.clone(),
+            roles: self.roles.clone(),
+            history: self.history.clone(),
+            matches: self.matches.clone(),
+        }
+    }
+
+    /// Starts using `roster`, returning the roster that was used before.
+    fn swap_roster(&mut self, mut roster: SavedRoster) -> SavedRoster {
+        use std::mem::swap;
+
+        swap(&mut self.roster_name, &mut roster.name);
+        swap(&mut self.players, &mut roster.players);
+        swap(&mut self.absent, &mut roster.absent);
+        swap(&mut self.number_of_teams, &mut roster.number_of_teams);
+        swap(&mut self.constraints, &mut roster.constraints);
+        swap(&mut self.team_sizes, &mut roster.team_sizes);
+        swap(&mut self.short_handed_bonus, &mut roster.short_handed_bonus);
+        swap(&mut self.players_per_team, &mut roster.players_per_team);
+        swap(&mut self.games_created, &mut roster.games_created);
+        swap(&mut self.attributes, &mut roster.attributes);
+        swap(&mut self.roles, &mut roster.roles);
+        swap(&mut self.history, &mut roster.history);
+        swap(&mut self.matches, &mut roster.matches);
+        roster
+    }
+
+    /// A link that opens the roster being used in someone else's browser.
+    #[cfg(target_arch = "wasm32")]
+    pub(super) fn share_link(&self) -> Option<String> {
+        crate::web::share_link(&Shared::Roster {
+            name: self.roster_name.clone(),
+            players: self.players.iter().map(SharedPlayer::from).collect(),
+            attributes: self.attributes.clone(),
+            roles: self.roles.clone(),
+        })
+    }
+
+    /// Whether a roster is already called `name`, ignoring case.
+    fn roster_name_taken(&self, name: &str) -> bool {
+        self.roster_name.eq_ignore_ascii_case(name) || self.other_roster_named(name)
+    }
+
+    /// Whether a roster other than the one being used is called `name`, ignoring case.
+    fn other_roster_named(&self, name: &str) -> bool {
+        self.other_rosters
+            .iter()
+            .any(|roster| roster.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The first of `names` no roster is called yet.
+    fn unused_roster_name(&self, mut names: impl Iterator<Item = String>) -> String {
+        names
+            .find(|name| !self.roster_name_taken(name))
+            .expect("There are endless names to try")
+    }
+
+    /// The rosters not being used with their index in `other_rosters`, as they are listed.
+    fn sorted_other_rosters(&self) -> Vec<(usize, &SavedRoster)> {
+        let mut others: Vec<(usize, &SavedRoster)> =
+            self.other_rosters.iter().enumerate().collect();
+        others.sort_by_key(|(_, roster)| roster.name.to_lowercase());
+        others
+    }
+
+    /// Switches to the roster at `idx` of `other_rosters`, setting the current one aside.
+    fn switch_roster(&mut self, idx: usize) {
+        let roster = self.other_rosters.remove(idx);
+        let previous = self.swap_roster(roster);
+        self.other_rosters.push(previous);
+    }
+}
+
+impl TeamCreator {
+    /// Picker of the roster being used, with buttons to manage rosters.
+    pub(super) fn show_rosters(&mut self, ui: &mut egui::Ui) {
+        let mut switch_to = None;
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Roster:");
+
+            if let Some(new_name) = &mut self.renaming_roster {
+                let text_box = ui.add(egui::TextEdit::singleline(new_name).desired_width(120.0));
+                let new_name = new_name.trim().to_owned();
+                let problem = if new_name.is_empty() {
+                    Some("The name can't be empty")
+                } else if self.persistent_data.other_roster_named(&new_name) {
+                    Some("Another roster already has this name")
+                } else {
+                    None
+                };
+
+                let save = ui
+                    .add_enabled(problem.is_none(), egui::Button::new("💾"))
+                    .on_disabled_hover_text(problem.unwrap_or_default());
+                if problem.is_none() && (save.clicked() || text_box.pressed_enter(ui.ctx())) {
+                    self.persistent_data.roster_name = new_name;
+                    self.renaming_roster = None;
+                    self.persistent_data.save_to_disk();
+                }
+                if ui.button("❌").on_hover_text("Keep the old name").clicked() {
+                    self.renaming_roster = None;
+                }
+                return;
+            }
+
+            let others = self.persistent_data.sorted_other_rosters();
+
+            egui::ComboBox::from_id_salt("roster")
+                .selected_text(self.persistent_data.roster_name.as_str_or("<unnamed>"))
+                .show_ui(ui, |ui| {
+                    let _ = ui.selectable_label(
+                        true,
+                        self.persistent_data.roster_name.as_str_or("<unnamed>"),
+                    );
+                    for (idx, roster) in others {
+                        if ui
+                            .selectable_label(false, roster.name.as_str_or("<unnamed>"))
+                            .clicked()
+                        {
+                            switch_to = Some(idx);
+                        }
+                    }
+                });
+
+            if ui.button("✏ Rename").clicked() {
+                self.renaming_roster = Some(self.persistent_data.roster_name.clone());
+            }
+            if ui.button("➕ New").clicked() {
+                let name = self
+                    .persistent_data
+                    .unused_roster_name((1..).map(|n| format!("Roster {}", n)));
+                let previous = self.persistent_data.swap_roster(SavedRoster::new(name));
+                self.persistent_data.other_rosters.push(previous);
+                changed = true;
+            }
+            if ui
+                .button("📄 Duplicate")
+                .on_hover_text("Make a copy of this roster and switch to it")
+                .clicked()
+            {
+                let mut copy = self.persistent_data.current_roster();
+                copy.name = self
+                    .persistent_data
+                    .unused_roster_name(numbered(&copy.name, "copy"));
+                let previous = self.persistent_data.swap_roster(copy);
+                self.persistent_data.other_rosters.push(previous);
+                changed = true;
+            }
+            #[cfg(target_arch = "wasm32")]
+            if ui
+                .button("🔗 Share link")
+                .on_hover_text("Copy a link that opens this roster in someone else's browser")
+                .clicked()
+                && let Some(link) = self.persistent_data.share_link()
+            {
+                ui.ctx().copy_text(link);
+            }
+            if ui
+                .add_enabled(
+                    !self.persistent_data.other_rosters.is_empty(),
+                    egui::Button::new("🗑 Delete"),
+                )
+                .clicked()
+            {
+                self.confirm_roster_delete = true;
+            }
+        });
+
+        if self.confirm_roster_delete {
+            let modal = Modal::new(Id::new("Delete Roster Modal")).show(ui.ctx(), |ui| {
+                ui.set_width(250.0);
+                ui.heading("Delete roster?");
+                ui.separator();
+                ui.label(format!(
+                    "\"{}\" and everything recorded for it will be gone for good.",
+                    self.persistent_data.roster_name.as_str_or("<unnamed>")
+                ));
+                ui.separator();
+                egui::Sides::new().show(
+                    ui,
+                    |_ui| {},
+                    |ui| {
+                        if ui.button("🗑 Delete").clicked() {
+                            let (first, _) = self.persistent_data.sorted_other_rosters()[0];
+                            let next = self.persistent_data.other_rosters.remove(first);
+                            self.persistent_data.swap_roster(next);
+                            changed = true;
+                            ui.close();
+                        }
+
+                        if ui.button("❌ Cancel").clicked() {
+                            ui.close();
+                        }
+                    },
+                );
+            });
+
+            if modal.should_close() {
+                self.confirm_roster_delete = false;
+            }
+        }
+
+        if let Some(idx) = switch_to {
+            self.persistent_data.switch_roster(idx);
+            changed = true;
+        }
+        if changed {
+            self.roster_changed();
+        }
+    }
+
+    /// Adds a roster someone shared and switches to it. The roster being used is set aside.
+    pub(super) fn import_roster(
+        &mut self,
+        name: &str,
+        players: &[SharedPlayer],
+        attributes: &[Attribute],
+        roles: &[Role],
+    ) {
+        let data = &mut self.persistent_data;
+        let name = name.as_str_or("Shared");
+        let name = data
+            .unused_roster_name(std::iter::once(name.to_owned()).chain(numbered(name, "shared")));
+
+        let mut roster = SavedRoster::new(name);
+        roster.players = players.iter().map(SharedPlayer::to_player).collect();
+        roster.attributes = attributes.to_vec();
+        roster.roles = roles.to_vec();
+        let previous = data.swap_roster(roster);
+        data.other_rosters.push(previous);
+        self.roster_changed();
+    }
+
+    /// Forgets everything shown for the previous roster.
+    fn roster_changed(&mut self) {
+        self.renaming_roster = None;
+        self.teams.clear();
+        self.bench.clear();
+        self.candidates.clear();
+        self.draft = None;
+        self.pick_timer = None;
+        self.current_game = None;
+        self.split_error = None;
+        self.player_being_edited = None;
+        self.new_constraint.players.clear();
+        self.tab = Tab::TeamCreator;
+        self.update_ratings();
+        self.persistent_data.save_to_disk();
+    }
+}
+
+/// "`name` (`label`)", then "`name` (`label` 2)" and so on.
+fn numbered<'a>(name: &'a str, label: &'a str) -> impl Iterator<Item = String> + 'a {
+    (1..).map(move |n| match n {
+        1 => format!("{} ({})", name, label),
+        n => format!("{} ({} {})", name, label, n),
+    })
+}
@@ -0,0 +1,28 @@
+//! The page the web build runs in.
+
+use eframe::wasm_bindgen::JsValue;
+use team_creator::share::{self, ShareError, Shared};
+
+/// Address of the page, without its fragment.
+pub fn page_url() -> Option<String> {
+    let href = web_sys::window()?.location().href().ok()?;
+    Some(href.split('#').next().unwrap_or_default().to_owned())
+}
+
+/// A link that opens the app with `shared` offered for import.
+pub fn share_link(shared: &Shared) -> Option<String> {
+    Some(format!("{}{}", page_url()?, share::to_fragment(shared)))
+}
+
+/// What the page was opened to share, if anything. The fragment is taken off the address, so
+/// reloading the page doesn't offer it again.
+pub fn take_shared() -> Option<Result<Shared, ShareError>> {
+    let window = web_sys::window()?;
+    let fragment = window.location().hash().ok()?;
+    let shared = share::from_fragment(&fragment)?;
+
+    if let (Some(url), Ok(history)) = (page_url(), window.history()) {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+    }
+    Some(shared)
+}
@@ -0,0 +1,103 @@
+//! Choosing a winner on a weighted spin wheel.
+
+use rand::Rng;
+use std::f32::consts::PI;
+use ulid::Ulid;
+
+/// Slowest speed a spin starts with, in radians per frame.
+pub const SPIN_VELOCITY_MIN: f32 = 0.1;
+/// Fastest speed a spin starts with, in radians per frame.
+pub const SPIN_VELOCITY_MAX: f32 = 0.7;
+/// Share of its speed the wheel keeps from one frame to the next.
+pub const BREAKING_PERCENT: f32 = 0.99500182845904523536028747135266250_f32;
+/// Speed below which the wheel stops.
+pub const MIN_SPEED: f32 = 0.00020;
+
+/// An entry on the wheel. Its slice is `weight` times as large as that of an entry of weight one.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Choice {
+    pub id: Ulid,
+    pub label: String,
+    pub weight: u32,
+}
+
+impl Choice {
+    /// A new choice, of weight one unless given.
+    pub fn new(label: String, weight: Option<u32>) -> Self {
+        Self {
+            label: label.to_string(),
+            weight: weight.unwrap_or(1),
+            id: Ulid::new(),
+        }
+    }
+}
+
+/// Position and speed of a spinning wheel. The wheel slows down by the same amount every frame,
+/// so where it stops only depends on how it started.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Spin {
+    /// Angle of the start of the first slice, in radians.
+    pub rotation: f32,
+    /// Radians turned in the next frame.
+    pub velocity: f32,
+}
+
+impl Spin {
+    /// A spin from a random angle at a random speed.
+    pub fn start(rng: &mut impl Rng) -> Self {
+        Spin {
+            rotation: rng.random_range(0.0..(2.0 * PI)),
+            velocity: rng.random_range(SPIN_VELOCITY_MIN..SPIN_VELOCITY_MAX),
+        }
+    }
+
+    /// Advances one frame. Returns `false` once the wheel has come to a stop.
+    pub fn step(&mut self) -> bool {
+        self.rotation += self.velocity;
+        self.velocity *= BREAKING_PERCENT;
+
+        if self.velocity.abs() < MIN_SPEED {
+            self.velocity = 0.0;
+            return false;
+        }
+        true
+    }
+
+    /// Runs the spin until it stops and returns the final rotation.
+    pub fn finish(mut self) -> f32 {
+        while self.step() {}
+        self.rotation
+    }
+}
+
+/// Spins the wheel to the end and returns the choice it lands on.
+pub fn spin<'a>(choices: &'a [Choice], rng: &mut impl Rng) -> Option<&'a Choice> {
+    winner(Spin::start(rng).finish(), choices)
+}
+
+/// The choice under the pointer, at angle zero, when the first slice starts at `rotation`.
+pub fn winner(rotation: f32, choices: &[Choice]) -> Option<&Choice> {
+    let angle_step = 2.0 * PI / total_weight(choices) as f32;
+    let mut last_angle = rotation;
+    let mut minimum: Option<(&Choice, f32)> = None;
+
+    for choice in choices {
+        let actual_end_angle = (last_angle + angle_step * choice.weight as f32) % (2.0 * PI);
+
+        if minimum
+            .as_ref()
+            .is_none_or(|&(_, min_angle)| actual_end_angle < min_angle)
+        {
+            minimum = Some((choice, actual_end_angle));
+        }
+
+        last_angle += angle_step * choice.weight as f32;
+    }
+
+    minimum.map(|(choice, _)| choice)
+}
+
+/// Sum of the weights of all choices.
+pub fn total_weight(choices: &[Choice]) -> u32 {
+    choices.iter().map(|choice| choice.weight).sum()
+}
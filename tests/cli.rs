@@ -0,0 +1,80 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the CLI on `roster`, given on standard input, and returns what it prints.
+fn run(roster: &str, args: &[&str]) -> String {
+    let output = run_raw(roster, args);
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn run_raw(roster: &str, args: &[&str]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_team-creator-cli"))
+        .args(args)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("The CLI starts");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(roster.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// A roster without ids, like a hand-written one, so every run makes up new ones.
+fn roster(count: usize, teams: usize) -> String {
+    let players: Vec<String> = (0..count)
+        .map(|idx| format!("(name:\"P{}\",skill:{})", idx, idx * 377 % 2000))
+        .collect();
+    format!(
+        "(players:[{}],number_of_teams:{})",
+        players.join(","),
+        teams
+    )
+}
+
+#[test]
+fn same_seed_same_teams() {
+    for (count, teams) in [(10, 3), (20, 2)] {
+        let roster = roster(count, teams);
+        let first = run(&roster, &["-s", "0000ABCD"]);
+        assert!(first.ends_with("Seed: 0000ABCD\n"), "{}", first);
+        for _ in 0..2 {
+            assert_eq!(run(&roster, &["-s", "0000ABCD"]), first);
+        }
+    }
+}
+
+#[test]
+fn oversized_skills_are_refused() {
+    let output = run_raw(
+        "(players:[(name:\"Ann\",skill:3000000000),(name:\"Bob\",skill:3000000000)])",
+        &[],
+    );
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: Ann has a skill or attribute over 35000\n"
+    );
+}
+
+#[test]
+fn saves_of_every_version_are_read() {
+    let roster = roster(4, 2);
+    let unversioned = run(&roster, &["-s", "1"]);
+    let current = format!("(version:2,data:{})", roster);
+    assert_eq!(run(&current, &["-s", "1"]), unversioned);
+
+    let newer = run_raw(&format!("(version:3,data:{})", roster), &[]);
+    assert!(!newer.status.success());
+    assert!(
+        String::from_utf8(newer.stderr)
+            .unwrap()
+            .contains("saved by a newer version of the app")
+    );
+}